@@ -0,0 +1,409 @@
+use core::fmt::Debug;
+
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::{
+    config::{apply_bitfield, Bitfield},
+    register::{Bank0, Mreg1, RegisterBank},
+    AccelOdr,
+    Error,
+    Icm42670,
+    Interface,
+    SensorError,
+};
+
+/// Output data rate of the DMP when running the pedometer, in Hz
+const PEDOMETER_ODR: f32 = 50.0;
+
+/// Configuration of the APEX features, as read back from the device
+///
+/// The thresholds and timers are reported as the raw selector codes written
+/// to their registers; refer to the datasheet for the value each represents.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ApexConfig {
+    /// Is the DMP allowed to enter power save mode?
+    pub dmp_power_save: bool,
+    /// Is the pedometer enabled?
+    pub pedometer_enabled: bool,
+    /// Is tilt detection enabled?
+    pub tilt_enabled: bool,
+    /// Is free fall detection enabled?
+    pub free_fall_enabled: bool,
+    /// Is significant motion detection enabled?
+    pub significant_motion_enabled: bool,
+    /// `PED_AMP_TH_SEL`: minimum acceleration peak to detect a step
+    pub ped_amp_threshold: u8,
+    /// `PED_STEP_CNT_TH_SEL`: steps required before the counter increments
+    pub ped_step_count_threshold: u8,
+    /// `PED_STEP_DET_TH_SEL`: steps required before step detection
+    /// interrupts are raised
+    pub ped_step_detect_threshold: u8,
+    /// `PED_SB_TIMER_TH_SEL`: duration used to validate a step buffer
+    pub ped_step_buffer_timer: u8,
+    /// `PED_HI_EN_TH_SEL`: threshold for rejecting high-energy vibrations
+    pub ped_high_energy_threshold: u8,
+    /// `TILT_WAIT_TIME_SEL`: duration of tilt required to detect it
+    pub tilt_wait_time: u8,
+    /// `SLEEP_TIME_OUT`: inactivity before the pedometer enters sleep
+    pub ped_sleep_timeout: u8,
+    /// `MOUNTING_MATRIX`: orientation of the device relative to the body
+    pub mounting_matrix: u8,
+    /// `SENSITIVITY_MODE`: is the pedometer in its slow walk mode?
+    pub slow_walk: bool,
+    /// `SMD_SENSITIVITY_SEL`: robustness of significant motion detection
+    /// against false detections
+    pub smd_sensitivity: u8,
+}
+
+/// Activity recognized by the pedometer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activity {
+    Unknown,
+    Walk,
+    Run,
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Activity::Unknown
+    }
+}
+
+impl Activity {
+    /// Decode the `APEX_DATA3` register
+    fn from_register(reg: u8) -> Self {
+        // `ACTIVITY_CLASS` occupies bits 1:0 in the register
+        match reg & 0b11 {
+            1 => Activity::Walk,
+            2 => Activity::Run,
+            _ => Activity::Unknown,
+        }
+    }
+}
+
+/// Sensitivity of the pedometer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PedometerMode {
+    /// Suited to walking and running at a normal pace
+    Normal,
+    /// More sensitive, to detect the steps of a slow walk, at the cost of
+    /// more false positives
+    SlowWalk,
+}
+
+impl Default for PedometerMode {
+    fn default() -> Self {
+        PedometerMode::Normal
+    }
+}
+
+/// Robustness of significant motion detection against false detections
+///
+/// Each level corresponds to a `SMD_SENSITIVITY_SEL` code, and higher levels
+/// reject more false detections.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SmdSensitivity {
+    Level0 = 0b000,
+    Level1 = 0b001,
+    Level2 = 0b010,
+    Level3 = 0b011,
+    Level4 = 0b100,
+}
+
+impl Default for SmdSensitivity {
+    fn default() -> Self {
+        Self::Level0
+    }
+}
+
+impl Bitfield for SmdSensitivity {
+    const BITMASK: u8 = 0b0000_1110;
+
+    fn bits(self) -> u8 {
+        // `SMD_SENSITIVITY_SEL` occupies bits 3:1 in the register
+        (self as u8) << 1
+    }
+}
+
+impl TryFrom<u8> for SmdSensitivity {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use SmdSensitivity::*;
+
+        match value {
+            0b000 => Ok(Level0),
+            0b001 => Ok(Level1),
+            0b010 => Ok(Level2),
+            0b011 => Ok(Level3),
+            0b100 => Ok(Level4),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Outputs of the APEX features
+///
+/// The ICM-42670 has no tap detection, so no tap data is reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ApexData {
+    /// Number of steps counted by the pedometer
+    pub step_count: u16,
+    /// Walk or run cadence, as the number of samples at the DMP output data
+    /// rate between consecutive steps
+    pub step_cadence: f32,
+    /// Activity recognized by the pedometer
+    pub activity: Activity,
+    /// Is the DMP idle?
+    pub dmp_idle: bool,
+    /// Duration of the most recent free fall, as a number of samples at the
+    /// DMP output data rate
+    pub free_fall_duration: u16,
+}
+
+impl ApexData {
+    /// Decode the `APEX_DATA0` through `APEX_DATA5` registers, in that order
+    pub(crate) fn from_registers(regs: [u8; 6]) -> Self {
+        let [data0, data1, data2, data3, data4, data5] = regs;
+
+        Self {
+            step_count: u16::from_le_bytes([data0, data1]),
+            // `STEP_CADENCE` is an unsigned fixed-point value with two
+            // fractional bits
+            step_cadence: data2 as f32 / 4.0,
+            activity: Activity::from_register(data3),
+            // `DMP_IDLE` occupies bit 2 in the register
+            dmp_idle: data3 & 0b0000_0100 != 0,
+            free_fall_duration: u16::from_be_bytes([data4, data5]),
+        }
+    }
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Read back the configuration of the APEX features
+    ///
+    /// Writes to the MREG1 bank are silently discarded if the internal clock
+    /// is not running, so this is the only way to confirm that an APEX
+    /// configuration has taken effect. The MREG1 bank cannot be read in
+    /// [`crate::PowerMode::Sleep`].
+    pub fn read_apex_config(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<ApexConfig, Error<E>> {
+        let bit = |reg: u8, n: u8| reg & (1 << n) != 0;
+        let field = |reg: u8, shift: u8, mask: u8| (reg >> shift) & mask;
+
+        let mut apex = [0u8; 2];
+        self.read_regs(&Bank0::APEX_CONFIG0, &mut apex)?;
+        let [config0, config1] = apex;
+
+        let config2 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG2)?;
+        let config3 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG3)?;
+        let config4 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG4)?;
+        let config5 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG5)?;
+        let config9 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG9)?;
+
+        Ok(ApexConfig {
+            dmp_power_save: bit(config0, 3),
+            significant_motion_enabled: bit(config1, 6),
+            free_fall_enabled: bit(config1, 5),
+            tilt_enabled: bit(config1, 4),
+            pedometer_enabled: bit(config1, 3),
+            ped_amp_threshold: field(config2, 4, 0x0F),
+            ped_step_count_threshold: field(config2, 0, 0x0F),
+            ped_step_detect_threshold: field(config3, 5, 0x07),
+            ped_step_buffer_timer: field(config3, 2, 0x07),
+            ped_high_energy_threshold: field(config3, 0, 0x03),
+            tilt_wait_time: field(config4, 6, 0x03),
+            ped_sleep_timeout: field(config4, 3, 0x07),
+            mounting_matrix: field(config5, 0, 0x07),
+            slow_walk: bit(config9, 0),
+            smd_sensitivity: field(config9, 1, 0x07),
+        })
+    }
+
+    /// Enable the pedometer with the given sensitivity
+    ///
+    /// The pedometer requires the accelerometer to run at 50Hz, so
+    /// [`SensorError::InvalidConfig`] is returned for any other output data
+    /// rate. The DMP output data rate is set to 50Hz to match. The DMP must
+    /// first have been initialized using [`Icm42670::init_dmp`]; the
+    /// sensitivity is held in the MREG1 bank, which cannot be written in
+    /// [`crate::PowerMode::Sleep`]. Read the outputs with
+    /// [`Icm42670::read_apex_data`].
+    pub fn enable_pedometer(
+        &mut self,
+        mode: PedometerMode,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        self.require_dmp()?;
+        self.require_accel_odr_50hz("the pedometer requires an accelerometer ODR of 50Hz")?;
+
+        // `SENSITIVITY_MODE` occupies bit 0 in the register
+        let config9 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG9)?;
+        let config9 = match mode {
+            PedometerMode::Normal => config9 & !0b0000_0001,
+            PedometerMode::SlowWalk => config9 | 0b0000_0001,
+        };
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG9, config9)?;
+
+        // `PED_ENABLE` occupies bit 3 and `DMP_ODR` bits 1:0 in the register,
+        // where 0b10 selects 50Hz
+        self.update_reg(&Bank0::APEX_CONFIG1, 0b0000_1010, 0b0000_1011)
+    }
+
+    /// Enable tilt detection
+    ///
+    /// A tilt is detected once the device has been held at more than 35° from
+    /// its previous orientation for the duration configured in the MREG1
+    /// bank. As with the pedometer, the accelerometer must run at 50Hz, and
+    /// the DMP must first have been initialized using [`Icm42670::init_dmp`].
+    /// The DMP output data rate is set to 50Hz to match.
+    pub fn enable_tilt_detection(&mut self) -> Result<(), Error<E>> {
+        self.require_dmp()?;
+        self.require_accel_odr_50hz("tilt detection requires an accelerometer ODR of 50Hz")?;
+
+        // `TILT_ENABLE` occupies bit 4 and `DMP_ODR` bits 1:0 in the register,
+        // where 0b10 selects 50Hz
+        self.update_reg(&Bank0::APEX_CONFIG1, 0b0001_0010, 0b0001_0011)
+    }
+
+    /// Has a tilt been detected?
+    ///
+    /// This clears the latched APEX status bits; any other APEX events they
+    /// held are still reported by [`Icm42670::poll_events`].
+    pub fn tilt_detected(&mut self) -> Result<bool, Error<E>> {
+        // `TILT_DET_INT` occupies bit 3 in the register
+        let detected = self.read_status_reg(Bank0::INT_STATUS3)? & 0b0000_1000 != 0;
+
+        Ok(detected)
+    }
+
+    /// Enable significant motion detection
+    ///
+    /// Significant motion is detected after wake on motion has been
+    /// triggered repeatedly over several seconds, so it is raised once for
+    /// sustained motion rather than for every movement. Wake on motion must
+    /// therefore be enabled first, using [`Icm42670::enable_wom`], otherwise
+    /// [`SensorError::InvalidConfig`] is returned; the DMP must also have been
+    /// initialized using [`Icm42670::init_dmp`].
+    ///
+    /// The sensitivity is held in the MREG1 bank, which cannot be written in
+    /// [`crate::PowerMode::Sleep`].
+    pub fn enable_smd(
+        &mut self,
+        sensitivity: SmdSensitivity,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        self.require_dmp()?;
+
+        // `WOM_EN` occupies bit 0 in the register
+        if self.read_reg(&Bank0::WOM_CONFIG)? & 0b0000_0001 == 0 {
+            return Err(Error::SensorError(SensorError::InvalidConfig {
+                reason: "significant motion detection requires wake on motion to be enabled",
+            }));
+        }
+
+        let config9 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG9)?;
+        let config9 = apply_bitfield(config9, sensitivity);
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG9, config9)?;
+
+        // `SMD_ENABLE` occupies bit 6 in the register
+        self.update_reg(&Bank0::APEX_CONFIG1, 0b0100_0000, 0b0100_0000)
+    }
+
+    /// Has significant motion been detected?
+    ///
+    /// This clears the latched wake on motion status bits; the axes which
+    /// triggered wake on motion are still reported by
+    /// [`Icm42670::poll_events`].
+    pub fn smd_triggered(&mut self) -> Result<bool, Error<E>> {
+        // `SMD_INT` occupies bit 3 in the register
+        let triggered = self.read_status_reg(Bank0::INT_STATUS2)? & 0b0000_1000 != 0;
+
+        Ok(triggered)
+    }
+
+    /// Return the pedometer's current cadence, in steps per second
+    ///
+    /// The device reports the cadence as the number of DMP samples between
+    /// consecutive steps, which is converted assuming the 50Hz DMP output
+    /// data rate set by [`Icm42670::enable_pedometer`]. Zero is returned if
+    /// no steps have been detected.
+    pub fn step_cadence(&mut self) -> Result<f32, Error<E>> {
+        // `STEP_CADENCE` is an unsigned fixed-point value with two fractional
+        // bits
+        let samples = self.read_reg(&Bank0::APEX_DATA2)? as f32 / 4.0;
+
+        if samples == 0.0 {
+            Ok(0.0)
+        } else {
+            Ok(PEDOMETER_ODR / samples)
+        }
+    }
+
+    /// Return the activity currently recognized by the pedometer
+    ///
+    /// [`SensorError::SensorNotActive`] is returned unless the pedometer has
+    /// been enabled with [`Icm42670::enable_pedometer`], since the
+    /// classification is otherwise meaningless.
+    pub fn activity_class(&mut self) -> Result<Activity, Error<E>> {
+        self.require_dmp()?;
+
+        // `PED_ENABLE` occupies bit 3 in the register
+        if self.read_reg(&Bank0::APEX_CONFIG1)? & 0b0000_1000 == 0 {
+            return Err(Error::SensorError(SensorError::SensorNotActive {
+                sensor: "pedometer",
+            }));
+        }
+
+        Ok(Activity::from_register(self.read_reg(&Bank0::APEX_DATA3)?))
+    }
+
+    /// Read the outputs of every APEX feature
+    ///
+    /// The pedometer outputs are read in a single transaction, so the step
+    /// count, cadence, and activity are always consistent with one another.
+    /// The free fall duration is held in separate registers and is read in a
+    /// second transaction.
+    pub fn read_apex_data(&mut self) -> Result<ApexData, Error<E>> {
+        let mut regs = [0u8; 6];
+        self.read_regs(&Bank0::APEX_DATA0, &mut regs[..4])?;
+        self.read_regs(&Bank0::APEX_DATA4, &mut regs[4..])?;
+
+        Ok(ApexData::from_registers(regs))
+    }
+
+    /// Return the number of steps counted since the previous call, or since
+    /// the DMP was initialized
+    ///
+    /// The device has no operation which reads and clears the step count at
+    /// once, so rather than reading and then resetting it, which would lose
+    /// any steps counted in between, the driver remembers the count at each
+    /// call and returns the difference. No steps are ever lost or counted
+    /// twice, provided that this is called before the counter wraps around.
+    pub fn take_step_count(&mut self) -> Result<u16, Error<E>> {
+        let mut regs = [0u8; 2];
+        self.read_regs(&Bank0::APEX_DATA0, &mut regs)?;
+
+        let count = u16::from_le_bytes(regs);
+        let steps = count.wrapping_sub(self.step_count_taken);
+        self.step_count_taken = count;
+
+        Ok(steps)
+    }
+
+    /// Return [`SensorError::InvalidConfig`] with `reason` unless the
+    /// accelerometer is configured to run at 50Hz
+    fn require_accel_odr_50hz(&self, reason: &'static str) -> Result<(), Error<E>> {
+        if self.config.accel_odr != AccelOdr::Hz50 {
+            return Err(Error::SensorError(SensorError::InvalidConfig { reason }));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,356 @@
+use core::fmt::Debug;
+
+use accelerometer::vector::{F32x3, I16x3};
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::{
+    delay_ms,
+    math,
+    register::{Mreg1, RegisterBank},
+    Config,
+    Error,
+    Icm42670,
+    Interface,
+    PowerMode,
+};
+
+/// Resolution of the accelerometer user offsets, in counts per g
+const ACCEL_OFFSET_PER_G: f32 = 2000.0;
+/// Resolution of the gyroscope user offsets, in counts per deg/sec
+const GYRO_OFFSET_PER_DPS: f32 = 32.0;
+
+/// Zero-rate offsets of the accelerometer and gyroscope
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Bias {
+    /// Accelerometer offset of each axis, in g, with gravity removed
+    pub accel: F32x3,
+    /// Gyroscope offset of each axis, in deg/sec
+    pub gyro: F32x3,
+}
+
+/// Spread of the readings from which a [`Bias`] was measured
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BiasQuality {
+    /// Standard deviation of each accelerometer axis, in g
+    pub accel_std: F32x3,
+    /// Standard deviation of each gyroscope axis, in deg/sec
+    pub gyro_std: F32x3,
+}
+
+/// Running sums of the readings of each axis and of their squares
+#[derive(Default)]
+struct Moments {
+    sum: [f32; 3],
+    sum_sq: [f32; 3],
+}
+
+impl Moments {
+    fn add(&mut self, value: F32x3) {
+        for (i, v) in [value.x, value.y, value.z].into_iter().enumerate() {
+            self.sum[i] += v;
+            self.sum_sq[i] += v * v;
+        }
+    }
+
+    fn mean(&self, n: f32) -> F32x3 {
+        F32x3::new(self.sum[0] / n, self.sum[1] / n, self.sum[2] / n)
+    }
+
+    /// Population standard deviation of each axis
+    fn std_dev(&self, n: f32) -> F32x3 {
+        let std = |i: usize| {
+            let mean = self.sum[i] / n;
+
+            // Rounding can make the variance of a constant signal slightly
+            // negative
+            math::sqrt((self.sum_sq[i] / n - mean * mean).max(0.0))
+        };
+
+        F32x3::new(std(0), std(1), std(2))
+    }
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Measure the sensor offsets by averaging `samples` readings
+    ///
+    /// The device must be stationary, with one axis aligned to gravity, and
+    /// both sensors must be running. The 1g of gravity is removed from
+    /// whichever accelerometer axis measures the largest magnitude. At least
+    /// one sample is always taken.
+    pub fn calibrate_bias(
+        &mut self,
+        samples: u16,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<Bias, Error<E>> {
+        let (bias, _) = self.calibrate_bias_with_quality(samples, delay)?;
+
+        Ok(bias)
+    }
+
+    /// Measure the sensor offsets as [`Icm42670::calibrate_bias`] does, and
+    /// also return the spread of the readings they were averaged from
+    ///
+    /// A large standard deviation shows that the device moved or vibrated
+    /// during the measurement, so that the offsets should not be trusted.
+    pub fn calibrate_bias_with_quality(
+        &mut self,
+        samples: u16,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(Bias, BiasQuality), Error<E>> {
+        let samples = samples.max(1);
+        let mut accel = Moments::default();
+        let mut gyro = Moments::default();
+        let accel_scale = self.config.accel_range.scale_factor();
+        let gyro_scale = self.config.gyro_range.scale_factor();
+
+        for _ in 0..samples {
+            self.wait_data_ready(delay)?;
+
+            // The data is scaled here rather than read normalized, so that
+            // neither the software offsets, smoothing, nor the deadband can
+            // hide the offset being measured.
+            let a = self.read_accel_raw()?;
+            let g = self.gyro_raw()?;
+            accel.add(scale(a, accel_scale));
+            gyro.add(scale(g, gyro_scale));
+        }
+
+        let n = samples as f32;
+        let quality = BiasQuality {
+            accel_std: accel.std_dev(n),
+            gyro_std: gyro.std_dev(n),
+        };
+        let mut accel = accel.mean(n);
+        let gyro = gyro.mean(n);
+
+        let abs = |v: f32| if v < 0.0 { -v } else { v };
+        let (ax, ay, az) = (abs(accel.x), abs(accel.y), abs(accel.z));
+        let up = if ax >= ay && ax >= az {
+            &mut accel.x
+        } else if ay >= az {
+            &mut accel.y
+        } else {
+            &mut accel.z
+        };
+        *up -= if *up < 0.0 { -1.0 } else { 1.0 };
+
+        Ok((Bias { accel, gyro }, quality))
+    }
+
+    /// Return the mean of `samples` consecutive normalized accelerometer
+    /// readings, in g
+    ///
+    /// A new reading is awaited before each sample, so no reading is counted
+    /// twice. Smoothing is not applied, since averaging already reduces the
+    /// noise. At least one sample is always taken.
+    pub fn read_accel_averaged(
+        &mut self,
+        samples: u16,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<F32x3, Error<E>> {
+        let samples = samples.max(1);
+        let mut accel = F32x3::default();
+
+        for _ in 0..samples {
+            self.wait_data_ready(delay)?;
+
+            let (_, a) = self.read_accel_filtered()?;
+            accel = F32x3::new(accel.x + a.x, accel.y + a.y, accel.z + a.z);
+        }
+
+        let n = samples as f32;
+
+        Ok(F32x3::new(accel.x / n, accel.y / n, accel.z / n))
+    }
+
+    /// Subtract `bias`, in deg/sec, from each axis of subsequent normalized
+    /// gyro readings
+    ///
+    /// The offsets are applied by the driver rather than the device, so they
+    /// are not applied to the raw or FIFO data, but are not limited in range
+    /// as those written by [`Icm42670::write_offsets`] are. They are applied
+    /// before smoothing and the deadband.
+    pub fn set_gyro_bias(&mut self, bias: [f32; 3]) {
+        let [x, y, z] = bias;
+        self.software_bias.gyro = F32x3::new(x, y, z);
+    }
+
+    /// Compensate the software gyro bias for changes in temperature
+    ///
+    /// The bias subtracted from each axis becomes
+    /// `bias + coeff * (temp - reference_temp)`, where `coeff` is in
+    /// deg/sec/°C and `reference_temp` is the temperature, in °C, at which the
+    /// bias set by [`Icm42670::set_gyro_bias`] or
+    /// [`Icm42670::calibrate_gyro`] was measured. Each normalized gyro read
+    /// then also reads the temperature, except for [`Icm42670::read_all`],
+    /// which already reads it in the same transaction. Pass `None` to stop
+    /// compensating.
+    pub fn set_gyro_bias_temp_coeff(&mut self, coeff: Option<([f32; 3], f32)>) {
+        self.gyro_temp_coeff =
+            coeff.map(|([x, y, z], reference_temp)| (F32x3::new(x, y, z), reference_temp));
+    }
+
+    /// Subtract `offset`, in g, from each axis of subsequent normalized
+    /// accelerometer readings
+    ///
+    /// As with [`Icm42670::set_gyro_bias`], the offsets are applied by the
+    /// driver, before smoothing. The offset should have gravity removed, as
+    /// the accelerometer offset of a [`Bias`] does.
+    pub fn set_accel_offset(&mut self, offset: [f32; 3]) {
+        let [x, y, z] = offset;
+        self.software_bias.accel = F32x3::new(x, y, z);
+    }
+
+    /// Measure the gyroscope offsets by averaging `samples` readings, and
+    /// subtract them from subsequent normalized readings
+    ///
+    /// The device must be stationary, and the gyroscope running; unlike
+    /// [`Icm42670::calibrate_bias`], the accelerometer is not needed. Any
+    /// previous software offset is replaced rather than refined. At least one
+    /// sample is always taken. The measured offsets are returned, in deg/sec.
+    pub fn calibrate_gyro(
+        &mut self,
+        samples: u16,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<F32x3, Error<E>> {
+        let samples = samples.max(1);
+        let mut gyro = Moments::default();
+        let gyro_scale = self.config.gyro_range.scale_factor();
+
+        for _ in 0..samples {
+            self.wait_data_ready(delay)?;
+
+            let g = self.gyro_raw()?;
+            gyro.add(scale(g, gyro_scale));
+        }
+
+        let bias = gyro.mean(samples as f32);
+        self.software_bias.gyro = bias;
+
+        Ok(bias)
+    }
+
+    /// The software gyro bias at `temp`, in °C.
+    pub(crate) fn gyro_bias_at(&self, temp: f32) -> F32x3 {
+        let bias = self.software_bias.gyro;
+
+        match self.gyro_temp_coeff {
+            Some((coeff, reference_temp)) => {
+                let delta = temp - reference_temp;
+
+                F32x3::new(
+                    bias.x + coeff.x * delta,
+                    bias.y + coeff.y * delta,
+                    bias.z + coeff.z * delta,
+                )
+            }
+            None => bias,
+        }
+    }
+
+    /// The software gyro bias at the current temperature, which is only read
+    /// if the bias is compensated for it.
+    pub(crate) fn gyro_bias_now(&mut self) -> Result<F32x3, Error<E>> {
+        if self.gyro_temp_coeff.is_none() {
+            return Ok(self.software_bias.gyro);
+        }
+
+        let temp = self.temperature()?;
+
+        Ok(self.gyro_bias_at(temp))
+    }
+
+    /// Program the hardware user offset registers to cancel out `bias`
+    ///
+    /// The offsets are applied by the device to all subsequent data,
+    /// including the FIFO, until it is reset or powered down. Each offset is
+    /// clamped to the range of its register: ±1g for the accelerometer and
+    /// ±64 deg/sec for the gyroscope.
+    pub fn write_offsets(
+        &mut self,
+        bias: &Bias,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        let gx = offset_counts(bias.gyro.x, GYRO_OFFSET_PER_DPS);
+        let gy = offset_counts(bias.gyro.y, GYRO_OFFSET_PER_DPS);
+        let gz = offset_counts(bias.gyro.z, GYRO_OFFSET_PER_DPS);
+        let ax = offset_counts(bias.accel.x, ACCEL_OFFSET_PER_G);
+        let ay = offset_counts(bias.accel.y, ACCEL_OFFSET_PER_G);
+        let az = offset_counts(bias.accel.z, ACCEL_OFFSET_PER_G);
+
+        // Each offset is 12 bits wide, with the upper nibbles of adjacent
+        // offsets sharing a register.
+        let low = |v: u16| v as u8;
+        let high = |v: u16| (v >> 8) as u8 & 0x0F;
+        let regs = [
+            (Mreg1::OFFSET_USER0, low(gx)),
+            (Mreg1::OFFSET_USER1, high(gy) << 4 | high(gx)),
+            (Mreg1::OFFSET_USER2, low(gy)),
+            (Mreg1::OFFSET_USER3, low(gz)),
+            (Mreg1::OFFSET_USER4, high(ax) << 4 | high(gz)),
+            (Mreg1::OFFSET_USER5, low(ax)),
+            (Mreg1::OFFSET_USER6, low(ay)),
+            (Mreg1::OFFSET_USER7, high(az) << 4 | high(ay)),
+            (Mreg1::OFFSET_USER8, low(az)),
+        ];
+
+        for (reg, value) in regs {
+            self.write_mreg(delay, RegisterBank::MReg1, &reg, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reset the device, measure its offsets, and store them in the hardware
+    /// user offset registers
+    ///
+    /// The device must be stationary for the duration; see
+    /// [`Icm42670::calibrate_bias`]. Resetting first ensures that no
+    /// previously stored offsets skew the measurement. The current
+    /// configuration is restored afterwards, and the measured bias is
+    /// returned so that it can be logged.
+    pub fn calibrate_and_store(
+        &mut self,
+        samples: u16,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<Bias, Error<E>> {
+        let config = self.sleep_config.unwrap_or(self.config);
+
+        self.soft_reset_blocking(delay)?;
+
+        // Both sensors must be running to be measured. The gyroscope takes up
+        // to 45ms to start producing valid data.
+        self.apply_config(Config {
+            power_mode: PowerMode::SixAxisLowNoise,
+            ..config
+        })?;
+        delay_ms(delay, 50);
+
+        let bias = self.calibrate_bias(samples, delay)?;
+        self.write_offsets(&bias, delay)?;
+        self.apply_config(config)?;
+
+        Ok(bias)
+    }
+}
+
+/// Convert an offset to the negated two's complement 12-bit value expected
+/// by the user offset registers
+fn offset_counts(value: f32, counts_per_unit: f32) -> u16 {
+    let counts = math::round(-value * counts_per_unit).clamp(-2048.0, 2047.0);
+
+    counts as i16 as u16
+}
+
+/// Scale a raw reading by the sensitivity of its range
+fn scale(raw: I16x3, scale: f32) -> F32x3 {
+    F32x3::new(
+        raw.x as f32 / scale,
+        raw.y as f32 / scale,
+        raw.z as f32 / scale,
+    )
+}
@@ -1,4 +1,4 @@
-use crate::error::SensorError;
+use crate::{error::SensorError, math};
 
 pub(crate) trait Bitfield {
     const BITMASK: u8;
@@ -8,8 +8,57 @@ pub(crate) trait Bitfield {
     fn bits(self) -> u8;
 }
 
+/// Replace the bits of `reg` covered by the field's bitmask with the field's
+/// value, leaving all other bits untouched
+pub(crate) fn apply_bitfield<F: Bitfield>(reg: u8, field: F) -> u8 {
+    (reg & !F::BITMASK) | (field.bits() & F::BITMASK)
+}
+
+/// The value of the field within `reg`, shifted down to its discriminant
+pub(crate) fn extract_bitfield<F: Bitfield>(reg: u8) -> u8 {
+    (reg & F::BITMASK) >> F::BITMASK.trailing_zeros()
+}
+
+/// A frequency, in Hz
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Hz(pub f32);
+
+impl From<Hz> for f32 {
+    fn from(hz: Hz) -> Self {
+        hz.0
+    }
+}
+
+/// Order of the bytes making up a multi-byte value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ByteOrder {
+    /// Most significant byte first
+    BigEndian,
+    /// Least significant byte first
+    LittleEndian,
+}
+
+impl ByteOrder {
+    /// Combine the values of two consecutive registers, in address order
+    pub(crate) fn i16_from_bytes(self, bytes: [u8; 2]) -> i16 {
+        match self {
+            ByteOrder::BigEndian => i16::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => i16::from_le_bytes(bytes),
+        }
+    }
+
+    /// Combine the values of two consecutive registers, in address order
+    pub(crate) fn u16_from_bytes(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+        }
+    }
+}
+
 /// I²C slave addresses, determined by the logic level of pin `AP_AD0`
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Address {
     /// `AP_AD0` pin == 0
     Primary = 0x68,
@@ -17,8 +66,26 @@ pub enum Address {
     Secondary = 0x69,
 }
 
+impl TryFrom<u8> for Address {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use Address::*;
+
+        match value {
+            0x68 => Ok(Primary),
+            0x69 => Ok(Secondary),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
 /// Configurable ranges of the Accelerometer
+///
+/// `ACCEL_UI_FS_SEL` is a two-bit field, and every one of its four values is
+/// modelled here; the ICM-42670 has no further full-scale ranges.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AccelRange {
     /// ±2G
     G2 = 3,
@@ -32,7 +99,10 @@ pub enum AccelRange {
 
 impl AccelRange {
     /// Sensitivity scale factor
-    pub fn scale_factor(&self) -> f32 {
+    ///
+    /// The device applies its factory sensitivity trim before the data is
+    /// output, so this nominal value is correct for every part.
+    pub const fn scale_factor(&self) -> f32 {
         use AccelRange::*;
 
         // Values taken from Table 2 of the data sheet
@@ -43,6 +113,20 @@ impl AccelRange {
             G16 => 2_048.0,
         }
     }
+
+    /// Sensitivity scale factor for 20-bit high-resolution data
+    ///
+    /// High-resolution samples carry four additional least-significant bits,
+    /// so each unit spans 16 times as many LSBs. Note that the device fixes
+    /// the accelerometer at ±16G while high-resolution mode is enabled.
+    pub fn scale_factor_hires(&self) -> f32 {
+        self.scale_factor() * 16.0
+    }
+
+    /// Convert a raw accelerometer reading taken at this range to g
+    pub fn to_g(&self, raw: i16) -> f32 {
+        raw as f32 / self.scale_factor()
+    }
 }
 
 impl Bitfield for AccelRange {
@@ -77,7 +161,11 @@ impl TryFrom<u8> for AccelRange {
 }
 
 /// Configurable ranges of the Gyroscope
+///
+/// As with the accelerometer, these are the only four ranges which
+/// `GYRO_UI_FS_SEL` can select.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum GyroRange {
     /// ±250 deg/sec
     Deg250 = 3,
@@ -91,7 +179,10 @@ pub enum GyroRange {
 
 impl GyroRange {
     /// Sensitivity scale factor
-    pub fn scale_factor(&self) -> f32 {
+    ///
+    /// The device applies its factory sensitivity trim before the data is
+    /// output, so this nominal value is correct for every part.
+    pub const fn scale_factor(&self) -> f32 {
         use GyroRange::*;
 
         // Values taken from Table 1 of the data sheet
@@ -102,6 +193,25 @@ impl GyroRange {
             Deg2000 => 16.4,
         }
     }
+
+    /// Sensitivity scale factor for 20-bit high-resolution data
+    ///
+    /// High-resolution samples carry four additional least-significant bits,
+    /// so each unit spans 16 times as many LSBs. Note that the device fixes
+    /// the gyroscope at ±2000 deg/sec while high-resolution mode is enabled.
+    pub fn scale_factor_hires(&self) -> f32 {
+        self.scale_factor() * 16.0
+    }
+
+    /// Convert a raw gyroscope reading taken at this range to deg/sec
+    pub fn to_dps(&self, raw: i16) -> f32 {
+        raw as f32 / self.scale_factor()
+    }
+
+    /// Convert a raw gyroscope reading taken at this range to rad/sec
+    pub fn to_rads(&self, raw: i16) -> f32 {
+        self.to_dps(raw).to_radians()
+    }
 }
 
 impl Bitfield for GyroRange {
@@ -137,6 +247,7 @@ impl TryFrom<u8> for GyroRange {
 
 /// Configurable power modes of the IMU
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum PowerMode {
     /// Gyroscope: OFF, Accelerometer: OFF
     Sleep = 0b00000,
@@ -154,6 +265,53 @@ pub enum PowerMode {
     Idle = 0b10000,
 }
 
+impl PowerMode {
+    /// Is the accelerometer running in this mode?
+    pub fn accel_enabled(self) -> bool {
+        use PowerMode::*;
+
+        matches!(self, AccelLowPower | AccelLowNoise | SixAxisLowNoise)
+    }
+
+    /// Is the gyroscope running in this mode?
+    ///
+    /// In [`PowerMode::Standby`] only the gyroscope's drive circuitry is
+    /// running, so it produces no data but this still returns `true`.
+    pub fn gyro_enabled(self) -> bool {
+        use PowerMode::*;
+
+        matches!(self, Standby | GyroLowNoise | SixAxisLowNoise)
+    }
+
+    /// Accelerometer output data rates supported in this mode, ordered from
+    /// fastest to slowest
+    ///
+    /// This is empty if the accelerometer is not running in this mode.
+    pub fn supported_accel_odrs(self) -> &'static [AccelOdr] {
+        use PowerMode::*;
+
+        match self {
+            AccelLowNoise | SixAxisLowNoise => AccelOdr::LOW_NOISE,
+            AccelLowPower => AccelOdr::LOW_POWER,
+            Sleep | Standby | GyroLowNoise | Idle => &[],
+        }
+    }
+
+    /// Gyroscope output data rates supported in this mode, ordered from
+    /// fastest to slowest
+    ///
+    /// This is empty if the gyroscope produces no data in this mode, including
+    /// in [`PowerMode::Standby`].
+    pub fn supported_gyro_odrs(self) -> &'static [GyroOdr] {
+        use PowerMode::*;
+
+        match self {
+            GyroLowNoise | SixAxisLowNoise => &GyroOdr::ALL,
+            Sleep | Standby | AccelLowPower | AccelLowNoise | Idle => &[],
+        }
+    }
+}
+
 impl Bitfield for PowerMode {
     const BITMASK: u8 = 0b0001_1111;
 
@@ -176,13 +334,94 @@ impl TryFrom<u8> for PowerMode {
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         use PowerMode::*;
 
+        if value & !Self::BITMASK != 0 {
+            return Err(SensorError::InvalidDiscriminant);
+        }
+
+        // `IDLE` occupies bit 4 in the register, and keeps the RC oscillator
+        // running. This is only observable when both sensors are off, as
+        // otherwise the oscillator is running regardless.
+        let idle = value & 0b1_0000 != 0;
+
+        match (idle, value & 0b1111) {
+            (false, 0b0000) => Ok(Sleep),
+            (true, 0b0000) => Ok(Idle),
+            (_, 0b0100) => Ok(Standby),
+            (_, 0b0010) => Ok(AccelLowPower),
+            (_, 0b0011) => Ok(AccelLowNoise),
+            (_, 0b1100) => Ok(GyroLowNoise),
+            (_, 0b1111) => Ok(SixAxisLowNoise),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Operating modes of the accelerometer, set independently of the gyroscope
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccelMode {
+    /// Accelerometer off
+    Off = 0b00,
+    /// Accelerometer duty-cycled
+    LowPower = 0b10,
+    /// Accelerometer on
+    LowNoise = 0b11,
+}
+
+impl Bitfield for AccelMode {
+    const BITMASK: u8 = 0b0000_0011;
+
+    fn bits(self) -> u8 {
+        // `ACCEL_MODE` occupies bits 1:0 in the register
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for AccelMode {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use AccelMode::*;
+
+        // Both 0b00 and 0b01 turn the accelerometer off
+        match value {
+            0b00 | 0b01 => Ok(Off),
+            0b10 => Ok(LowPower),
+            0b11 => Ok(LowNoise),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Operating modes of the gyroscope, set independently of the accelerometer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GyroMode {
+    /// Gyroscope off
+    Off = 0b00,
+    /// Only the gyroscope's drive circuitry is on, allowing a fast start
+    Standby = 0b01,
+    /// Gyroscope on
+    LowNoise = 0b11,
+}
+
+impl Bitfield for GyroMode {
+    const BITMASK: u8 = 0b0000_1100;
+
+    fn bits(self) -> u8 {
+        // `GYRO_MODE` occupies bits 3:2 in the register
+        (self as u8) << 2
+    }
+}
+
+impl TryFrom<u8> for GyroMode {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use GyroMode::*;
+
         match value {
-            0b0000 => Ok(Sleep),
-            0b0100 => Ok(Standby),
-            0b0010 => Ok(AccelLowPower),
-            0b0011 => Ok(AccelLowNoise),
-            0b1100 => Ok(GyroLowNoise),
-            0b1111 => Ok(SixAxisLowNoise),
+            0b00 => Ok(Off),
+            0b01 => Ok(Standby),
+            0b11 => Ok(LowNoise),
             _ => Err(SensorError::InvalidDiscriminant),
         }
     }
@@ -190,6 +429,7 @@ impl TryFrom<u8> for PowerMode {
 
 /// Accelerometer ODR selection values
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AccelOdr {
     /// 1.6 kHz (LN mode)
     Hz1600 = 0b0101,
@@ -216,7 +456,73 @@ pub enum AccelOdr {
 }
 
 impl AccelOdr {
-    pub fn as_f32(self) -> f32 {
+    /// Every variant, ordered from fastest to slowest
+    const ALL: [Self; 11] = [
+        AccelOdr::Hz1600,
+        AccelOdr::Hz800,
+        AccelOdr::Hz400,
+        AccelOdr::Hz200,
+        AccelOdr::Hz100,
+        AccelOdr::Hz50,
+        AccelOdr::Hz25,
+        AccelOdr::Hz12_5,
+        AccelOdr::Hz6_25,
+        AccelOdr::Hz3_125,
+        AccelOdr::Hz1_5625,
+    ];
+
+    /// Output data rates supported in low-noise mode
+    const LOW_NOISE: &'static [Self] = &[
+        AccelOdr::Hz1600,
+        AccelOdr::Hz800,
+        AccelOdr::Hz400,
+        AccelOdr::Hz200,
+        AccelOdr::Hz100,
+        AccelOdr::Hz50,
+        AccelOdr::Hz25,
+        AccelOdr::Hz12_5,
+    ];
+
+    /// Output data rates supported in low-power mode
+    const LOW_POWER: &'static [Self] = &[
+        AccelOdr::Hz400,
+        AccelOdr::Hz200,
+        AccelOdr::Hz100,
+        AccelOdr::Hz50,
+        AccelOdr::Hz25,
+        AccelOdr::Hz12_5,
+        AccelOdr::Hz6_25,
+        AccelOdr::Hz3_125,
+        AccelOdr::Hz1_5625,
+    ];
+
+    /// Can the accelerometer run at this output data rate in `mode`?
+    ///
+    /// Every rate is valid in the modes in which the accelerometer is off, so
+    /// that it can be configured before being enabled.
+    pub fn is_valid_for(&self, mode: PowerMode) -> bool {
+        !mode.accel_enabled() || mode.supported_accel_odrs().contains(self)
+    }
+
+    /// Look up the output data rate with the given frequency, if there is one
+    pub fn from_hz(hz: Hz) -> Option<Self> {
+        Self::ALL.iter().copied().find(|v| v.as_f32() == hz.0)
+    }
+
+    /// The slowest output data rate whose sample period is no longer than
+    /// `ms` milliseconds, or `None` if even the fastest rate is too slow
+    ///
+    /// Choosing the slowest rate which meets a latency budget minimizes power
+    /// consumption.
+    pub fn for_max_period_ms(ms: f32) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .rev()
+            .copied()
+            .find(|v| 1000.0 / v.as_f32() <= ms)
+    }
+
+    pub const fn as_f32(self) -> f32 {
         use AccelOdr::*;
 
         match self {
@@ -235,6 +541,12 @@ impl AccelOdr {
     }
 }
 
+impl From<AccelOdr> for Hz {
+    fn from(value: AccelOdr) -> Self {
+        Hz(value.as_f32())
+    }
+}
+
 impl Bitfield for AccelOdr {
     const BITMASK: u8 = 0b0000_1111;
 
@@ -275,6 +587,7 @@ impl TryFrom<u8> for AccelOdr {
 
 /// Gyroscope ODR selection values
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum GyroOdr {
     /// 1.6k Hz
     Hz1600 = 0b0101,
@@ -295,7 +608,48 @@ pub enum GyroOdr {
 }
 
 impl GyroOdr {
-    pub fn as_f32(self) -> f32 {
+    /// Every variant, ordered from fastest to slowest
+    const ALL: [Self; 8] = [
+        GyroOdr::Hz1600,
+        GyroOdr::Hz800,
+        GyroOdr::Hz400,
+        GyroOdr::Hz200,
+        GyroOdr::Hz100,
+        GyroOdr::Hz50,
+        GyroOdr::Hz25,
+        GyroOdr::Hz12_5,
+    ];
+
+    /// Can the gyroscope run at this output data rate in `mode`?
+    ///
+    /// The gyroscope supports every output data rate in both of the modes in
+    /// which it runs, so this is always `true`; it is provided for symmetry
+    /// with [`AccelOdr::is_valid_for`].
+    pub fn is_valid_for(&self, mode: PowerMode) -> bool {
+        let supported = mode.supported_gyro_odrs();
+
+        supported.is_empty() || supported.contains(self)
+    }
+
+    /// Look up the output data rate with the given frequency, if there is one
+    pub fn from_hz(hz: Hz) -> Option<Self> {
+        Self::ALL.iter().copied().find(|v| v.as_f32() == hz.0)
+    }
+
+    /// The slowest output data rate whose sample period is no longer than
+    /// `ms` milliseconds, or `None` if even the fastest rate is too slow
+    ///
+    /// Choosing the slowest rate which meets a latency budget minimizes power
+    /// consumption.
+    pub fn for_max_period_ms(ms: f32) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .rev()
+            .copied()
+            .find(|v| 1000.0 / v.as_f32() <= ms)
+    }
+
+    pub const fn as_f32(self) -> f32 {
         use GyroOdr::*;
 
         match self {
@@ -311,6 +665,12 @@ impl GyroOdr {
     }
 }
 
+impl From<GyroOdr> for Hz {
+    fn from(value: GyroOdr) -> Self {
+        Hz(value.as_f32())
+    }
+}
+
 impl Bitfield for GyroOdr {
     const BITMASK: u8 = 0b0000_1111;
 
@@ -348,6 +708,7 @@ impl TryFrom<u8> for GyroOdr {
 
 /// Gyroscope Filter Bandwith selection values
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum GyroBw {
     /// BW filter bypassed
     Hz10000 = 0b000,
@@ -368,7 +729,24 @@ pub enum GyroBw {
 }
 
 impl GyroBw {
-    pub fn as_f32(self) -> f32 {
+    /// Every variant, ordered from widest to narrowest
+    const ALL: [Self; 8] = [
+        GyroBw::Hz10000,
+        GyroBw::Hz180,
+        GyroBw::Hz121,
+        GyroBw::Hz73,
+        GyroBw::Hz53,
+        GyroBw::Hz34,
+        GyroBw::Hz25,
+        GyroBw::Hz16,
+    ];
+
+    /// Look up the filter bandwidth with the given frequency, if there is one
+    pub fn from_hz(hz: Hz) -> Option<Self> {
+        Self::ALL.iter().copied().find(|v| v.as_f32() == hz.0)
+    }
+
+    pub const fn as_f32(self) -> f32 {
         use GyroBw::*;
 
         match self {
@@ -390,6 +768,12 @@ impl Default for GyroBw {
     }
 }
 
+impl From<GyroBw> for Hz {
+    fn from(value: GyroBw) -> Self {
+        Hz(value.as_f32())
+    }
+}
+
 impl Bitfield for GyroBw {
     const BITMASK: u8 = 0b0000_0111;
 
@@ -421,6 +805,7 @@ impl TryFrom<u8> for GyroBw {
 
 /// Accelareration Filter Bandwith selection values
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AccelBw {
     /// BW filter bypassed
     Hz10000 = 0b000,
@@ -441,7 +826,24 @@ pub enum AccelBw {
 }
 
 impl AccelBw {
-    pub fn as_f32(self) -> f32 {
+    /// Every variant, ordered from widest to narrowest
+    const ALL: [Self; 8] = [
+        AccelBw::Hz10000,
+        AccelBw::Hz180,
+        AccelBw::Hz121,
+        AccelBw::Hz73,
+        AccelBw::Hz53,
+        AccelBw::Hz34,
+        AccelBw::Hz25,
+        AccelBw::Hz16,
+    ];
+
+    /// Look up the filter bandwidth with the given frequency, if there is one
+    pub fn from_hz(hz: Hz) -> Option<Self> {
+        Self::ALL.iter().copied().find(|v| v.as_f32() == hz.0)
+    }
+
+    pub const fn as_f32(self) -> f32 {
         use AccelBw::*;
 
         match self {
@@ -463,6 +865,12 @@ impl Default for AccelBw {
     }
 }
 
+impl From<AccelBw> for Hz {
+    fn from(value: AccelBw) -> Self {
+        Hz(value.as_f32())
+    }
+}
+
 impl Bitfield for AccelBw {
     const BITMASK: u8 = 0b0000_0111;
 
@@ -491,3 +899,475 @@ impl TryFrom<u8> for AccelBw {
         }
     }
 }
+
+/// Source of the device's internal clock
+///
+/// The gyroscope requires the PLL, so with [`ClockSource::Pll`] the device
+/// switches to it automatically whenever the gyroscope is enabled, and back
+/// to the RC oscillator otherwise.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClockSource {
+    /// Always use the RC oscillator
+    Rc = 0b00,
+    /// Use the PLL when it is available, otherwise the RC oscillator
+    Pll = 0b01,
+    /// Disable every clock, giving the lowest possible power consumption
+    Disabled = 0b11,
+}
+
+impl Default for ClockSource {
+    fn default() -> Self {
+        Self::Pll
+    }
+}
+
+impl Bitfield for ClockSource {
+    const BITMASK: u8 = 0b0000_0011;
+
+    fn bits(self) -> u8 {
+        // `CLKSEL` occupies bits 1:0 in the register
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for ClockSource {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use ClockSource::*;
+
+        match value {
+            0b00 => Ok(Rc),
+            0b01 => Ok(Pll),
+            0b11 => Ok(Disabled),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Clock which duty-cycles the accelerometer in low-power mode
+///
+/// The wake-up oscillator draws the least current, but its period varies
+/// more than the RC oscillator's, so the samples are taken with more jitter.
+/// This matters most when the accelerometer is used for wake on motion.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccelLpClock {
+    /// Wake-up oscillator
+    WakeUpOscillator = 0,
+    /// RC oscillator
+    Rc = 1,
+}
+
+impl Default for AccelLpClock {
+    fn default() -> Self {
+        Self::WakeUpOscillator
+    }
+}
+
+impl Bitfield for AccelLpClock {
+    const BITMASK: u8 = 0b1000_0000;
+
+    fn bits(self) -> u8 {
+        // `ACCEL_LP_CLK_SEL` occupies bit 7 in the register
+        (self as u8) << 7
+    }
+}
+
+impl TryFrom<u8> for AccelLpClock {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use AccelLpClock::*;
+
+        match value {
+            0 => Ok(WakeUpOscillator),
+            1 => Ok(Rc),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Number of measurements averaged into each accelerometer sample in
+/// low-power mode
+///
+/// Averaging more measurements lowers the noise at the cost of a higher
+/// current draw, and limits the maximum output data rate. The setting has no
+/// effect in low-noise mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccelAvg {
+    X2 = 0b000,
+    X4 = 0b001,
+    X8 = 0b010,
+    X16 = 0b011,
+    X32 = 0b100,
+    X64 = 0b101,
+}
+
+impl AccelAvg {
+    /// Number of measurements averaged into each sample
+    pub const fn count(self) -> u8 {
+        use AccelAvg::*;
+
+        match self {
+            X2 => 2,
+            X4 => 4,
+            X8 => 8,
+            X16 => 16,
+            X32 => 32,
+            X64 => 64,
+        }
+    }
+}
+
+impl Default for AccelAvg {
+    fn default() -> Self {
+        Self::X2
+    }
+}
+
+impl Bitfield for AccelAvg {
+    const BITMASK: u8 = 0b0111_0000;
+
+    fn bits(self) -> u8 {
+        // `ACCEL_UI_AVG` occupies bits 6:4 in the register
+        (self as u8) << 4
+    }
+}
+
+impl TryFrom<u8> for AccelAvg {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use AccelAvg::*;
+
+        match value {
+            0b000 => Ok(X2),
+            0b001 => Ok(X4),
+            0b010 => Ok(X8),
+            0b011 => Ok(X16),
+            0b100 => Ok(X32),
+            0b101 => Ok(X64),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Configuration of the accelerometer alone
+///
+/// See [`Config::accel`] and [`Config::with_accel`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AccelConfig {
+    /// Full-scale range
+    pub range: AccelRange,
+    /// Output data rate
+    pub odr: AccelOdr,
+    /// Filter bandwidth
+    pub bw: AccelBw,
+}
+
+/// Configuration of the gyroscope alone
+///
+/// See [`Config::gyro`] and [`Config::with_gyro`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GyroConfig {
+    /// Full-scale range
+    pub range: GyroRange,
+    /// Output data rate
+    pub odr: GyroOdr,
+    /// Filter bandwidth
+    pub bw: GyroBw,
+}
+
+/// Complete configuration of the accelerometer and gyroscope
+///
+/// A configuration can be built up from the defaults using the `with_*`
+/// methods, such as [`Config::with_accel_odr`], and applied with
+/// [`crate::Icm42670::apply_config`] or [`crate::Icm42670::new_with_config`].
+/// Unsupported combinations of settings are rejected when the configuration is
+/// applied; see [`Config::validate`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Config {
+    /// Full-scale range of the accelerometer
+    pub accel_range: AccelRange,
+    /// Output data rate of the accelerometer
+    pub accel_odr: AccelOdr,
+    /// Filter bandwidth of the accelerometer
+    pub accel_bw: AccelBw,
+    /// Full-scale range of the gyroscope
+    pub gyro_range: GyroRange,
+    /// Output data rate of the gyroscope
+    pub gyro_odr: GyroOdr,
+    /// Filter bandwidth of the gyroscope
+    pub gyro_bw: GyroBw,
+    /// Power mode of the IMU
+    pub power_mode: PowerMode,
+}
+
+impl Config {
+    /// The accelerometer's settings
+    pub fn accel(&self) -> AccelConfig {
+        AccelConfig {
+            range: self.accel_range,
+            odr: self.accel_odr,
+            bw: self.accel_bw,
+        }
+    }
+
+    /// The gyroscope's settings
+    pub fn gyro(&self) -> GyroConfig {
+        GyroConfig {
+            range: self.gyro_range,
+            odr: self.gyro_odr,
+            bw: self.gyro_bw,
+        }
+    }
+
+    /// This configuration with the accelerometer's settings replaced
+    pub fn with_accel(self, accel: AccelConfig) -> Self {
+        Self {
+            accel_range: accel.range,
+            accel_odr: accel.odr,
+            accel_bw: accel.bw,
+            ..self
+        }
+    }
+
+    /// This configuration with the gyroscope's settings replaced
+    pub fn with_gyro(self, gyro: GyroConfig) -> Self {
+        Self {
+            gyro_range: gyro.range,
+            gyro_odr: gyro.odr,
+            gyro_bw: gyro.bw,
+            ..self
+        }
+    }
+
+    /// This configuration with the range of the accelerometer replaced
+    pub fn with_accel_range(self, accel_range: AccelRange) -> Self {
+        Self {
+            accel_range,
+            ..self
+        }
+    }
+
+    /// This configuration with the output data rate of the accelerometer
+    /// replaced
+    pub fn with_accel_odr(self, accel_odr: AccelOdr) -> Self {
+        Self { accel_odr, ..self }
+    }
+
+    /// This configuration with the filter bandwidth of the accelerometer
+    /// replaced
+    pub fn with_accel_bw(self, accel_bw: AccelBw) -> Self {
+        Self { accel_bw, ..self }
+    }
+
+    /// This configuration with the range of the gyroscope replaced
+    pub fn with_gyro_range(self, gyro_range: GyroRange) -> Self {
+        Self { gyro_range, ..self }
+    }
+
+    /// This configuration with the output data rate of the gyroscope replaced
+    pub fn with_gyro_odr(self, gyro_odr: GyroOdr) -> Self {
+        Self { gyro_odr, ..self }
+    }
+
+    /// This configuration with the filter bandwidth of the gyroscope replaced
+    pub fn with_gyro_bw(self, gyro_bw: GyroBw) -> Self {
+        Self { gyro_bw, ..self }
+    }
+
+    /// This configuration with the power mode replaced
+    pub fn with_power_mode(self, power_mode: PowerMode) -> Self {
+        Self { power_mode, ..self }
+    }
+
+    /// Check that every combination of settings is supported by the device
+    ///
+    /// Only the settings of the sensors which are enabled by the power mode
+    /// are checked:
+    ///
+    /// - In low-noise mode the accelerometer supports output data rates from
+    ///   12.5Hz, and in low-power mode up to 400Hz
+    /// - In low-noise mode the bandwidth of each sensor's low-pass filter must
+    ///   be below the Nyquist frequency of its output data rate, unless the
+    ///   filter is bypassed
+    pub fn validate(&self) -> Result<(), SensorError> {
+        use PowerMode::*;
+
+        let invalid = |reason| Err(SensorError::InvalidConfig { reason });
+
+        let accel_low_noise = matches!(self.power_mode, AccelLowNoise | SixAxisLowNoise);
+        let gyro_low_noise = matches!(self.power_mode, GyroLowNoise | SixAxisLowNoise);
+        let accel_odr = self.accel_odr.as_f32();
+        let gyro_odr = self.gyro_odr.as_f32();
+        let accel_odr_supported = self
+            .power_mode
+            .supported_accel_odrs()
+            .contains(&self.accel_odr);
+
+        if accel_low_noise && !accel_odr_supported {
+            return invalid("accelerometer ODR below 12.5Hz requires low-power mode");
+        }
+        if self.power_mode == AccelLowPower && !accel_odr_supported {
+            return invalid("accelerometer ODR above 400Hz requires low-noise mode");
+        }
+        if accel_low_noise
+            && self.accel_bw != AccelBw::Hz10000
+            && self.accel_bw.as_f32() >= accel_odr / 2.0
+        {
+            return invalid("accelerometer filter bandwidth exceeds the Nyquist frequency");
+        }
+        if gyro_low_noise
+            && self.gyro_bw != GyroBw::Hz10000
+            && self.gyro_bw.as_f32() >= gyro_odr / 2.0
+        {
+            return invalid("gyroscope filter bandwidth exceeds the Nyquist frequency");
+        }
+
+        Ok(())
+    }
+
+    /// Interval between accelerometer samples, in microseconds, or `None` if
+    /// the accelerometer is not running in the configured power mode
+    ///
+    /// In low-power mode the accelerometer is duty-cycled, but it is woken
+    /// once per sample period, so samples are still produced at the
+    /// configured output data rate; only the number of measurements averaged
+    /// into each sample differs. In low-power mode the sample clock may be
+    /// the less accurate wake-up oscillator, so timestamps derived from this
+    /// nominal period should be corrected against the FIFO timestamps where
+    /// accuracy matters.
+    pub fn effective_accel_period_us(&self) -> Option<u32> {
+        if self.power_mode.accel_enabled() {
+            Some(math::round(1_000_000.0 / self.accel_odr.as_f32()) as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Nyquist frequency, half of the output data rate, of the accelerometer
+    /// and gyroscope respectively
+    pub fn nyquist(&self) -> (Hz, Hz) {
+        (
+            Hz(self.accel_odr.as_f32() / 2.0),
+            Hz(self.gyro_odr.as_f32() / 2.0),
+        )
+    }
+
+    /// The widest filter bandwidths below the Nyquist frequency of each
+    /// sensor's output data rate, for the accelerometer and gyroscope
+    /// respectively
+    ///
+    /// At the lowest output data rates even the narrowest bandwidth exceeds
+    /// the Nyquist frequency, and the filter must be bypassed to satisfy
+    /// [`Config::validate`], so the bypass setting is returned instead.
+    pub fn recommended_bw(&self) -> (AccelBw, GyroBw) {
+        let (accel_nyquist, gyro_nyquist) = self.nyquist();
+
+        let accel = AccelBw::ALL
+            .iter()
+            .copied()
+            .filter(|&bw| bw != AccelBw::Hz10000)
+            .find(|bw| bw.as_f32() < accel_nyquist.0)
+            .unwrap_or(AccelBw::Hz10000);
+        let gyro = GyroBw::ALL
+            .iter()
+            .copied()
+            .filter(|&bw| bw != GyroBw::Hz10000)
+            .find(|bw| bw.as_f32() < gyro_nyquist.0)
+            .unwrap_or(GyroBw::Hz10000);
+
+        (accel, gyro)
+    }
+
+    /// Could either running sensor alias out-of-band vibration into its data?
+    ///
+    /// The ICM-42670 has no dedicated anti-aliasing filter; its low-pass
+    /// filter is the only band-limiting applied before the data is
+    /// decimated to the output data rate. Bypassing it in low-noise mode is
+    /// permitted by [`Config::validate`], but lets vibration above the
+    /// Nyquist frequency fold back into the measurement band, which this
+    /// reports as a warning rather than an error.
+    pub fn aliasing_risk(&self) -> bool {
+        use PowerMode::*;
+
+        let accel_low_noise = matches!(self.power_mode, AccelLowNoise | SixAxisLowNoise);
+        let gyro_low_noise = matches!(self.power_mode, GyroLowNoise | SixAxisLowNoise);
+
+        (accel_low_noise && self.accel_bw == AccelBw::Hz10000)
+            || (gyro_low_noise && self.gyro_bw == GyroBw::Hz10000)
+    }
+
+    /// Estimated group delay, in milliseconds, of the accelerometer and
+    /// gyroscope data respectively
+    ///
+    /// The datasheet does not specify the delay of the low-pass filters, so
+    /// each is modelled as a second-order Butterworth filter, whose group delay
+    /// at low frequencies is `√2 / (2π · bandwidth)`. Half of the output data
+    /// rate period is added to account for the averaging of each sample. The
+    /// estimate applies to low-noise mode.
+    pub fn group_delay_ms(&self) -> (f32, f32) {
+        let delay = |bw: Option<f32>, odr: f32| {
+            let filter = match bw {
+                Some(bw) => core::f32::consts::SQRT_2 / (2.0 * core::f32::consts::PI * bw),
+                None => 0.0,
+            };
+
+            (filter + 0.5 / odr) * 1000.0
+        };
+
+        let accel_bw = match self.accel_bw {
+            AccelBw::Hz10000 => None,
+            bw => Some(bw.as_f32()),
+        };
+        let gyro_bw = match self.gyro_bw {
+            GyroBw::Hz10000 => None,
+            bw => Some(bw.as_f32()),
+        };
+
+        (
+            delay(accel_bw, self.accel_odr.as_f32()),
+            delay(gyro_bw, self.gyro_odr.as_f32()),
+        )
+    }
+}
+
+/// Implement `Serialize` and `Deserialize` for enums, representing each
+/// variant by its discriminant and converting it back with `TryFrom<u8>`
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_discriminant {
+    ($($ty:ty),+) => {
+        $(
+            impl serde::Serialize for $ty {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    serializer.serialize_u8(*self as u8)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $ty {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let value = <u8 as serde::Deserialize>::deserialize(deserializer)?;
+
+                    Self::try_from(value).map_err(|_| {
+                        serde::de::Error::invalid_value(
+                            serde::de::Unexpected::Unsigned(value as u64),
+                            &stringify!($ty),
+                        )
+                    })
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "serde")]
+impl_serde_discriminant!(
+    Address, AccelRange, GyroRange, PowerMode, AccelOdr, GyroOdr, GyroBw, AccelBw
+);
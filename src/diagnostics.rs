@@ -0,0 +1,140 @@
+use core::fmt::Debug;
+
+use crate::{
+    config::{extract_bitfield, Bitfield},
+    fifo::FIFO_SIZE,
+    register::Bank0,
+    Config,
+    Error,
+    Icm42670,
+    IntStatus,
+    Interface,
+    SensorError,
+};
+
+/// Number of registers in User Bank 0, all of which are captured by
+/// [`Icm42670::dump_registers`]
+pub const REGISTER_DUMP_LEN: usize = 128;
+
+/// Features provided by the connected device
+///
+/// The ICM-42607 and ICM-42670, the only devices which the driver accepts,
+/// have identical feature sets, so this mostly documents which features are
+/// not available on either. The APEX engine has no tap detection and no
+/// raise-to-wake gesture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capabilities {
+    /// ID reported by the `WHO_AM_I` register
+    pub device_id: u8,
+    /// Is the APEX pedometer available?
+    pub has_pedometer: bool,
+    /// Is APEX tilt detection available?
+    pub has_tilt: bool,
+    /// Is APEX free fall detection available?
+    pub has_freefall: bool,
+    /// Is APEX significant motion detection available?
+    pub has_smd: bool,
+    /// Is wake on motion available?
+    pub has_wom: bool,
+    /// Is tap detection available?
+    pub has_tap: bool,
+    /// Is the raise-to-wake gesture available?
+    pub has_r2w: bool,
+    /// Capacity of the FIFO, in bytes
+    pub fifo_size_bytes: u16,
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Report the features of the connected device, identified by its
+    /// `WHO_AM_I` register
+    ///
+    /// [`SensorError::BadChip`] is returned for any device other than those
+    /// in [`Icm42670::DEVICE_IDS`].
+    pub fn capabilities(&mut self) -> Result<Capabilities, Error<E>> {
+        let device_id = self.device_id()?;
+        if !Self::DEVICE_IDS.contains(&device_id) {
+            return Err(Error::SensorError(SensorError::BadChip {
+                found: device_id,
+            }));
+        }
+
+        Ok(Capabilities {
+            device_id,
+            has_pedometer: true,
+            has_tilt: true,
+            has_freefall: true,
+            has_smd: true,
+            has_wom: true,
+            has_tap: false,
+            has_r2w: false,
+            fifo_size_bytes: FIFO_SIZE,
+        })
+    }
+
+    /// Read every register of User Bank 0 into `buffer`, returning the part
+    /// of it which was filled
+    ///
+    /// The byte at each offset is the value of the register at that address,
+    /// so dumps can be logged or compared with one from a known-good device.
+    /// `buffer` must hold at least [`REGISTER_DUMP_LEN`] bytes, otherwise
+    /// [`SensorError::OutOfRange`] is returned. `FIFO_DATA` is not read, as
+    /// that would remove a byte from the FIFO, and is reported as zero. The
+    /// interrupt status registers are cleared by the dump, but their
+    /// conditions are still reported by [`Icm42670::poll_events`].
+    pub fn dump_registers<'a>(&mut self, buffer: &'a mut [u8]) -> Result<&'a [u8], Error<E>> {
+        if buffer.len() < REGISTER_DUMP_LEN {
+            return Err(Error::SensorError(SensorError::OutOfRange));
+        }
+        let buffer = &mut buffer[..REGISTER_DUMP_LEN];
+
+        let fifo_data = Bank0::FIFO_DATA as usize;
+        let (before, after) = buffer.split_at_mut(fifo_data);
+        self.read_regs(&0u8, before)?;
+        after[0] = 0;
+        self.read_regs(&(fifo_data as u8 + 1), &mut after[1..])?;
+
+        let status = Bank0::INT_STATUS_DRDY as usize;
+        let mut regs = [0u8; 4];
+        regs.copy_from_slice(&buffer[status..status + 4]);
+        self.latch_status(IntStatus::from_registers(regs));
+
+        Ok(buffer)
+    }
+
+    /// Read back and decode the configuration of the sensors from the device
+    ///
+    /// Unlike the driver's cached configuration, this reflects any change
+    /// made to the device by other means, so comparing the two shows whether
+    /// they have diverged. The registers are read in a single transaction.
+    /// [`SensorError::InvalidDiscriminant`] is returned if any field holds a
+    /// value which no setting describes, such as a combination of sensor
+    /// modes set with [`Icm42670::set_accel_mode`].
+    pub fn read_device_config(&mut self) -> Result<Config, Error<E>> {
+        // PWR_MGMT0, GYRO_CONFIG0, ACCEL_CONFIG0, TEMP_CONFIG0, GYRO_CONFIG1,
+        // ACCEL_CONFIG1
+        let mut regs = [0u8; 6];
+        self.read_regs(&Bank0::PWR_MGMT0, &mut regs)?;
+
+        Ok(Config {
+            power_mode: decode(regs[0])?,
+            gyro_range: decode(regs[1])?,
+            gyro_odr: decode(regs[1])?,
+            accel_range: decode(regs[2])?,
+            accel_odr: decode(regs[2])?,
+            gyro_bw: decode(regs[4])?,
+            accel_bw: decode(regs[5])?,
+        })
+    }
+}
+
+/// Decode a field from the value of its register
+fn decode<F>(reg: u8) -> Result<F, SensorError>
+where
+    F: Bitfield + TryFrom<u8, Error = SensorError>,
+{
+    F::try_from(extract_bitfield::<F>(reg))
+}
@@ -7,15 +7,35 @@ pub enum Error<E> {
     SensorError(SensorError),
 }
 
+impl<E> Error<E> {
+    /// The underlying bus error, if this error originated from the bus
+    ///
+    /// The blocking I²C traits of `embedded-hal` 0.2 do not define any common
+    /// error kinds, so this driver is unable to tell a NACK apart from a bus
+    /// timeout or arbitration loss. The error returned by the HAL is always
+    /// preserved unchanged, so it can be inspected here to decide between
+    /// retrying the operation and recovering the bus.
+    pub fn bus_error(&self) -> Option<&E> {
+        match self {
+            Error::BusError(e) => Some(e),
+            Error::SensorError(_) => None,
+        }
+    }
+}
+
 /// Any type of error specific to this device
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SensorError {
     /// The chip at the specified address is not reporting the correct self
     /// identification code.
     ///
     /// For I²C this is most likely if the ID change jumper is in the wrong
     /// state or there is anther chip on the bus with this address.
-    BadChip,
+    BadChip {
+        /// Identification code which the chip reported
+        found: u8,
+    },
     /// Returned if the register bank is set to a invalid value
     ///
     /// There are 4 banks, 0-3
@@ -25,6 +45,63 @@ pub enum SensorError {
     /// Attempted to create an AccelRange or GyroRange enum from an invalid
     /// discriminant
     InvalidDiscriminant,
+    /// The device did not complete an operation in the allotted time
+    ///
+    /// This is most likely if the internal clock is not running, e.g. if the
+    /// device is in sleep mode.
+    Timeout {
+        /// Name of the operation which timed out
+        operation: &'static str,
+    },
+    /// Attempted to read data from a sensor which is not producing any in
+    /// the current power mode
+    SensorNotActive {
+        /// Name of the sensor
+        sensor: &'static str,
+    },
+    /// Attempted to use an APEX feature before initializing the DMP
+    DmpNotInitialized,
+    /// Reading back a register after writing it returned a different value
+    WriteVerifyFailed {
+        /// Address of the register
+        reg: u8,
+        /// Value which was written
+        expected: u8,
+        /// Value which was read back
+        got: u8,
+    },
+    /// Attempted an operation which depends on the FIFO packet format before
+    /// the FIFO was configured
+    FifoNotConfigured,
+    /// A FIFO packet does not have the format which the FIFO was configured
+    /// to produce
+    FifoFormatMismatch,
+    /// A value was outside the range supported by the device
+    OutOfRange,
+    /// A configuration could not be applied in full, as a register did not
+    /// read back as written, and the previous configuration was restored
+    ConfigRollback {
+        /// Address of the register
+        reg: u8,
+        /// Value which was written
+        expected: u8,
+        /// Value which was read back
+        got: u8,
+    },
+    /// Consecutive readings of a sensor were identical for longer than the
+    /// stuck detection threshold allows
+    ///
+    /// The sensor may have frozen, or the bus may be returning stale data.
+    StuckData {
+        /// Name of the sensor
+        sensor: &'static str,
+    },
+    /// A configuration contains a combination of settings which the device
+    /// does not support
+    InvalidConfig {
+        /// Description of the unsupported combination
+        reason: &'static str,
+    },
 }
 
 impl<E> From<SensorError> for Error<E> {
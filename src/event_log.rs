@@ -0,0 +1,110 @@
+use core::fmt::Debug;
+
+use crate::{Error, Icm42670, IntEvent, IntStatus, Interface};
+
+/// A fixed-capacity history of APEX events, each tagged with the time at
+/// which it was observed
+///
+/// Once `N` events have been recorded, each new event overwrites the oldest.
+/// Timestamps are supplied by the caller, in whatever unit its clock uses, so
+/// that the log is independent of the platform.
+#[derive(Clone, Copy, Debug)]
+pub struct EventLog<const N: usize> {
+    entries: [Option<(IntEvent, u32)>; N],
+    /// Index of the oldest entry
+    start: usize,
+    len: usize,
+}
+
+impl<const N: usize> Default for EventLog<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> EventLog<N> {
+    /// An empty log
+    pub const fn new() -> Self {
+        Self {
+            entries: [None; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Record every APEX event in `status`, observed at `timestamp`
+    ///
+    /// Any other events in `status` are ignored.
+    pub fn record(&mut self, status: &IntStatus, timestamp: u32) {
+        status.dispatch(|event| {
+            if event.is_apex() {
+                self.push(event, timestamp);
+            }
+        });
+    }
+
+    /// Remove and return the oldest entry
+    pub fn pop(&mut self) -> Option<(IntEvent, u32)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let entry = self.entries[self.start].take();
+        self.start = (self.start + 1) % N;
+        self.len -= 1;
+
+        entry
+    }
+
+    /// Number of entries in the log
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the log empty?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove every entry
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn push(&mut self, event: IntEvent, timestamp: u32) {
+        if N == 0 {
+            return;
+        }
+
+        let end = (self.start + self.len) % N;
+        self.entries[end] = Some((event, timestamp));
+
+        if self.len == N {
+            self.start = (self.start + 1) % N;
+        } else {
+            self.len += 1;
+        }
+    }
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Return every interrupt condition which has occurred since the previous
+    /// call, as [`Icm42670::poll_events`] does, also recording any APEX
+    /// events in `log`
+    ///
+    /// `now` is called once to timestamp the events.
+    pub fn poll_events_logged<const N: usize>(
+        &mut self,
+        log: &mut EventLog<N>,
+        now: fn() -> u32,
+    ) -> Result<IntStatus, Error<E>> {
+        let events = self.poll_events()?;
+        log.record(&events, now());
+
+        Ok(events)
+    }
+}
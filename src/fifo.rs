@@ -0,0 +1,798 @@
+use core::fmt::Debug;
+
+use accelerometer::vector::F32x3;
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::{
+    register::{Bank0, Mreg1, RegisterBank},
+    AccelRange,
+    ByteOrder,
+    Error,
+    FifoDataP4,
+    FifoPacketType,
+    GyroRange,
+    Icm42670,
+    Interface,
+    Sample,
+    SensorError,
+};
+
+/// Capacity of the FIFO, in bytes
+pub(crate) const FIFO_SIZE: u16 = 2304;
+
+// Bits of the header byte which begins every FIFO packet
+const HEADER_MSG: u8 = 0b1000_0000;
+const HEADER_ACCEL: u8 = 0b0100_0000;
+const HEADER_GYRO: u8 = 0b0010_0000;
+const HEADER_20: u8 = 0b0001_0000;
+const HEADER_TMST_FSYNC: u8 = 0b0000_1000;
+const HEADER_FSYNC: u8 = 0b0000_0100;
+const HEADER_ODR_ACCEL: u8 = 0b0000_0010;
+const HEADER_ODR_GYRO: u8 = 0b0000_0001;
+
+/// Decode the packets in a buffer of raw FIFO data directly into `out`,
+/// returning the number of samples written
+///
+/// Parsing stops at the first packet indicating that the FIFO was empty, at
+/// the first incomplete packet, or once `out` is full, whichever comes first.
+///
+/// Packets containing only accelerometer or only gyroscope data leave the
+/// other sensor's values at zero. High-resolution packets are always scaled
+/// using the maximum ranges, which the device enforces in that mode.
+pub fn parse_fifo_into(
+    raw: &[u8],
+    out: &mut [Sample],
+    accel_range: AccelRange,
+    gyro_range: GyroRange,
+) -> usize {
+    let mut count = 0;
+
+    for (slot, packet) in out.iter_mut().zip(Packets::new(raw)) {
+        let packet = DecodedPacket::decode(packet);
+        let (accel_scale, gyro_scale) = packet.scale_factors(accel_range, gyro_range);
+
+        *slot = Sample {
+            accel: packet
+                .accel
+                .map(|a| scale(a, accel_scale))
+                .unwrap_or_default(),
+            gyro: packet
+                .gyro
+                .map(|g| scale(g, gyro_scale))
+                .unwrap_or_default(),
+            temp: packet.temp,
+        };
+        count += 1;
+    }
+
+    count
+}
+
+/// Decode the packets in a buffer of raw FIFO data into `out`, returning the
+/// number of records written
+///
+/// Unlike [`parse_fifo_into`], each record also carries the packet's
+/// timestamp and header flags, which indicate whether an output data rate
+/// changed mid-stream. Parsing stops under the same conditions.
+pub fn parse_fifo_records(
+    raw: &[u8],
+    out: &mut [FifoRecord],
+    accel_range: AccelRange,
+    gyro_range: GyroRange,
+) -> usize {
+    let mut count = 0;
+
+    for (slot, packet) in out.iter_mut().zip(Packets::new(raw)) {
+        let packet = DecodedPacket::decode(packet);
+        let (accel_scale, gyro_scale) = packet.scale_factors(accel_range, gyro_range);
+
+        *slot = FifoRecord {
+            accel: packet.accel.map(|a| scale(a, accel_scale)),
+            gyro: packet.gyro.map(|g| scale(g, gyro_scale)),
+            temp: packet.temp,
+            timestamp: packet.timestamp,
+            header: packet.header,
+        };
+        count += 1;
+    }
+
+    count
+}
+
+/// Behaviour of the FIFO once it is full
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FifoMode {
+    /// Keep writing new packets, discarding the oldest
+    Stream,
+    /// Stop writing packets, discarding the newest
+    StopOnFull,
+}
+
+impl Default for FifoMode {
+    fn default() -> Self {
+        FifoMode::Stream
+    }
+}
+
+/// Configuration of the FIFO
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FifoConfig {
+    /// Format of the packets written to the FIFO, which determines whether
+    /// they contain accelerometer data, gyroscope data, or both
+    pub packet: FifoPacketType,
+    /// Behaviour of the FIFO once it is full
+    pub mode: FifoMode,
+}
+
+impl Default for FifoConfig {
+    /// Accelerometer and gyroscope data in stream mode
+    fn default() -> Self {
+        Self {
+            packet: FifoPacketType::Packet3,
+            mode: FifoMode::default(),
+        }
+    }
+}
+
+/// Outcome of [`Icm42670::drain_fifo_packets`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FifoReadStatus {
+    /// Number of bytes read, always a whole number of packets
+    pub bytes_read: usize,
+    /// Has the FIFO overflowed since the flag was last cleared, so that
+    /// packets may be missing?
+    pub overflowed: bool,
+}
+
+/// Flags carried in the header of a FIFO packet
+///
+/// An output data rate flag is set only on the first packet sampled at the
+/// new rate; every following packet was sampled at that rate too, until the
+/// next packet whose flag is set. The device clears the flags itself, so
+/// there is nothing to acknowledge. The new rate is not recorded in the
+/// packet, but is that returned by [`crate::Icm42670::accel_odr`] or
+/// [`crate::Icm42670::gyro_odr`] if the rate has changed only once since the
+/// FIFO was last read.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FifoHeader {
+    /// The accelerometer output data rate differs from that of the previous
+    /// packet containing accelerometer data
+    pub accel_odr_changed: bool,
+    /// The gyroscope output data rate differs from that of the previous
+    /// packet containing gyroscope data
+    pub gyro_odr_changed: bool,
+    /// The packet contains a timestamp
+    pub has_timestamp: bool,
+    /// The timestamp is the time of an FSYNC event, rather than of the sample
+    pub fsync: bool,
+}
+
+impl FifoHeader {
+    /// Decode the header byte which begins every FIFO packet
+    pub fn from_byte(header: u8) -> Self {
+        Self {
+            accel_odr_changed: header & HEADER_ODR_ACCEL != 0,
+            gyro_odr_changed: header & HEADER_ODR_GYRO != 0,
+            has_timestamp: header & HEADER_TMST_FSYNC != 0,
+            fsync: header & (HEADER_TMST_FSYNC | HEADER_FSYNC) == HEADER_TMST_FSYNC | HEADER_FSYNC,
+        }
+    }
+
+    /// Did either output data rate change?
+    pub fn odr_changed(&self) -> bool {
+        self.accel_odr_changed || self.gyro_odr_changed
+    }
+}
+
+/// The data contained in a single FIFO packet
+///
+/// See [`parse_fifo_records`] and [`FifoDecimator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FifoRecord {
+    /// Acceleration of each axis in g, if the packet contained a new sample
+    pub accel: Option<F32x3>,
+    /// Angular rate of each axis in deg/sec, if the packet contained a new
+    /// sample
+    pub gyro: Option<F32x3>,
+    /// Temperature, in degrees centigrade
+    ///
+    /// Every packet includes a temperature reading; the device has no option
+    /// to omit it. High-resolution packets carry a 16-bit reading with a
+    /// resolution of 1/128°C, and every other packet an 8-bit reading with a
+    /// resolution of 0.5°C.
+    pub temp: f32,
+    /// Timestamp of the packet in raw ticks, if the packet contained one
+    ///
+    /// The counter wraps around every 65,536 ticks. See
+    /// [`FifoRecord::timestamp_us`] to convert it to microseconds.
+    pub timestamp: Option<u16>,
+    /// Flags from the packet's header
+    pub header: FifoHeader,
+}
+
+impl FifoRecord {
+    /// Timestamp of the packet in microseconds, if the packet contained one
+    ///
+    /// `resolution` must match the resolution to which the device's
+    /// timestamp counter is configured. The result wraps around in the same
+    /// way as the raw counter.
+    pub fn timestamp_us(&self, resolution: TimestampResolution) -> Option<u32> {
+        self.timestamp
+            .map(|ticks| u32::from(ticks) * resolution.tick_us())
+    }
+}
+
+/// Resolution of the timestamp counter, selected by `TMST_RES`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimestampResolution {
+    /// One tick per microsecond
+    Us1,
+    /// One tick per 16 microseconds
+    Us16,
+}
+
+impl TimestampResolution {
+    /// Duration of a single tick, in microseconds
+    pub const fn tick_us(self) -> u32 {
+        match self {
+            TimestampResolution::Us1 => 1,
+            TimestampResolution::Us16 => 16,
+        }
+    }
+}
+
+impl Default for TimestampResolution {
+    fn default() -> Self {
+        TimestampResolution::Us1
+    }
+}
+
+/// A FIFO parser which drops repeated samples of the slower sensor
+///
+/// When the accelerometer and gyroscope run at different output data rates,
+/// the FIFO repeats the slower sensor's most recent sample in every packet.
+/// This parser compares each sensor's data to that of the previous packet,
+/// and omits it from the output if it is unchanged, so that each
+/// [`FifoRecord`] holds only new samples. Packets containing no new data at
+/// all are dropped entirely, unless their header reports that an output data
+/// rate changed.
+///
+/// The comparison is made on the raw data, so a genuinely new sample which is
+/// bit-for-bit identical to the previous one is also dropped. The parser
+/// remembers the previous packet across calls, so consecutive reads of the
+/// FIFO can be parsed separately; call [`FifoDecimator::reset`] after
+/// flushing the FIFO or reconfiguring it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FifoDecimator {
+    last_accel: Option<[i32; 3]>,
+    last_gyro: Option<[i32; 3]>,
+}
+
+impl FifoDecimator {
+    /// Create a parser which has not yet seen any packets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the previous packet, so that the data of the next packet is
+    /// always treated as new
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Decode the packets in a buffer of raw FIFO data into `out`, dropping
+    /// repeated samples, and return the number of records written
+    ///
+    /// Parsing stops under the same conditions as [`parse_fifo_into`]. Only
+    /// packets which have been decoded update the parser's state, so any
+    /// remaining packets may be parsed by a subsequent call.
+    pub fn parse_into(
+        &mut self,
+        raw: &[u8],
+        out: &mut [FifoRecord],
+        accel_range: AccelRange,
+        gyro_range: GyroRange,
+    ) -> usize {
+        let mut count = 0;
+        let mut packets = Packets::new(raw);
+
+        while count < out.len() {
+            let packet = match packets.next() {
+                Some(packet) => DecodedPacket::decode(packet),
+                None => break,
+            };
+            let (accel_scale, gyro_scale) = packet.scale_factors(accel_range, gyro_range);
+
+            let accel = fresh(&mut self.last_accel, packet.accel);
+            let gyro = fresh(&mut self.last_gyro, packet.gyro);
+
+            if accel.is_none() && gyro.is_none() && !packet.header.odr_changed() {
+                continue;
+            }
+
+            out[count] = FifoRecord {
+                accel: accel.map(|a| scale(a, accel_scale)),
+                gyro: gyro.map(|g| scale(g, gyro_scale)),
+                temp: packet.temp,
+                timestamp: packet.timestamp,
+                header: packet.header,
+            };
+            count += 1;
+        }
+
+        count
+    }
+}
+
+/// Return `value` if it differs from `last`, updating `last` in either case
+fn fresh(last: &mut Option<[i32; 3]>, value: Option<[i32; 3]>) -> Option<[i32; 3]> {
+    let value = value?;
+
+    if *last == Some(value) {
+        None
+    } else {
+        *last = Some(value);
+        Some(value)
+    }
+}
+
+/// Swap the byte order of every multi-byte value in a buffer of raw FIFO
+/// data, returning the number of bytes converted
+///
+/// The parsers expect big-endian data, which the device reports by default.
+/// Data read while [`Icm42670::set_data_endianness`] selects little-endian
+/// must be converted first. Only complete packets are converted, so a packet
+/// split across two reads is left as it is.
+pub fn swap_fifo_byte_order(raw: &mut [u8]) -> usize {
+    let mut offset = 0;
+
+    while let Some(len) = raw.get(offset).and_then(|&header| packet_len(header)) {
+        let packet = match raw.get_mut(offset..offset + len) {
+            Some(packet) => packet,
+            None => break,
+        };
+
+        // Every packet holds 16-bit values from byte 1. The 8-bit temperature
+        // follows them in 8 and 16 byte packets, and the 16 byte packet's
+        // timestamp follows that. The lower bits of the 20-bit values, in the
+        // last 3 bytes of a 20 byte packet, are single bytes.
+        let pairs: &[usize] = match len {
+            8 => &[1, 3, 5],
+            16 => &[1, 3, 5, 7, 9, 11, 14],
+            _ => &[1, 3, 5, 7, 9, 11, 13, 15],
+        };
+        for &i in pairs {
+            packet.swap(i, i + 1);
+        }
+
+        offset += len;
+    }
+
+    offset
+}
+
+/// Iterator over the complete packets in a buffer of raw FIFO data
+struct Packets<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> Packets<'a> {
+    fn new(raw: &'a [u8]) -> Self {
+        Self { raw }
+    }
+}
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = packet_len(*self.raw.first()?)?;
+        let packet = self.raw.get(..len)?;
+        self.raw = &self.raw[len..];
+
+        Some(packet)
+    }
+}
+
+/// Convert an 8-bit FIFO temperature reading to degrees centigrade
+///
+/// The reading is a signed value with a resolution of 0.5°C, unlike the
+/// 16-bit readings of the `TEMP_DATA` registers and high-resolution packets.
+pub(crate) fn temperature_8bit(raw: u8) -> f32 {
+    (raw as i8 as f32 / 2.0) + 25.0
+}
+
+/// Size in bytes of the FIFO packet beginning with `header`, or `None` if the
+/// header indicates that the FIFO is empty
+fn packet_len(header: u8) -> Option<usize> {
+    if header & HEADER_MSG != 0 {
+        None
+    } else if header & HEADER_20 != 0 {
+        Some(20)
+    } else if header & (HEADER_ACCEL | HEADER_GYRO) == HEADER_ACCEL | HEADER_GYRO {
+        Some(16)
+    } else {
+        Some(8)
+    }
+}
+
+/// Contents of a single FIFO packet, prior to scaling
+struct DecodedPacket {
+    header: FifoHeader,
+    accel: Option<[i32; 3]>,
+    gyro: Option<[i32; 3]>,
+    temp: f32,
+    timestamp: Option<u16>,
+    hires: bool,
+}
+
+impl DecodedPacket {
+    /// Decode a complete FIFO packet of any size
+    fn decode(packet: &[u8]) -> Self {
+        let header = packet[0];
+
+        if header & HEADER_20 != 0 {
+            return Self::decode_hires(packet);
+        }
+
+        let vector = |offset: usize| {
+            let axis =
+                |i: usize| i16::from_be_bytes([packet[offset + i], packet[offset + i + 1]]) as i32;
+
+            [axis(0), axis(2), axis(4)]
+        };
+
+        // The accelerometer data always precedes the gyroscope data, and the
+        // 8-bit temperature immediately follows whichever is last. Only 16 byte
+        // packets have room for a timestamp, which follows the temperature.
+        let mut offset = 1;
+        let mut accel = None;
+        let mut gyro = None;
+
+        if header & HEADER_ACCEL != 0 {
+            accel = Some(vector(offset));
+            offset += 6;
+        }
+        if header & HEADER_GYRO != 0 {
+            gyro = Some(vector(offset));
+            offset += 6;
+        }
+        let temp = temperature_8bit(packet[offset]);
+        let timestamp = if packet.len() == 16 && header & HEADER_TMST_FSYNC != 0 {
+            Some(u16::from_be_bytes([packet[14], packet[15]]))
+        } else {
+            None
+        };
+
+        Self {
+            header: FifoHeader::from_byte(header),
+            accel,
+            gyro,
+            temp,
+            timestamp,
+            hires: false,
+        }
+    }
+
+    /// Decode a 20 byte high-resolution FIFO packet
+    fn decode_hires(packet: &[u8]) -> Self {
+        let header = packet[0];
+        let raw = FifoDataP4::to_fifodata_raw(packet);
+
+        Self {
+            header: FifoHeader::from_byte(header),
+            accel: Some([raw.ax, raw.ay, raw.az]),
+            gyro: Some([raw.gx, raw.gy, raw.gz]),
+            temp: (raw.t as f32 / 128.0) + 25.0,
+            timestamp: if header & HEADER_TMST_FSYNC != 0 {
+                Some(raw.ts)
+            } else {
+                None
+            },
+            hires: true,
+        }
+    }
+
+    /// Factors by which to divide the accelerometer and gyroscope data of
+    /// this packet
+    fn scale_factors(&self, accel_range: AccelRange, gyro_range: GyroRange) -> (f32, f32) {
+        if self.hires {
+            (
+                AccelRange::G16.scale_factor_hires(),
+                GyroRange::Deg2000.scale_factor_hires(),
+            )
+        } else {
+            (accel_range.scale_factor(), gyro_range.scale_factor())
+        }
+    }
+}
+
+/// Scale the raw data of each axis
+fn scale(raw: [i32; 3], scale: f32) -> F32x3 {
+    F32x3::new(
+        raw[0] as f32 / scale,
+        raw[1] as f32 / scale,
+        raw[2] as f32 / scale,
+    )
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Does a FIFO packet beginning with `header` have the format which the
+    /// FIFO is configured to produce?
+    ///
+    /// This is always `false` if the FIFO has not been configured, or if the
+    /// header indicates that the FIFO is empty.
+    pub fn is_fifo_format(&self, header: u8) -> bool {
+        match self.fifo_packet {
+            Some(packet) => FifoPacketType::from_header(header) == Some(packet),
+            None => false,
+        }
+    }
+
+    /// Configure the FIFO and start writing packets to it
+    ///
+    /// The packet format is held in the MREG1 bank, so this cannot be called
+    /// in [`crate::PowerMode::Sleep`]. Packets are only written while the
+    /// sensors they contain are running. If the packets hold both
+    /// accelerometer and gyroscope data, the two output data rates must be
+    /// equal, as the slower sensor would otherwise repeat its samples across
+    /// consecutive packets; [`SensorError::InvalidConfig`] is returned if
+    /// they differ.
+    pub fn enable_fifo(
+        &mut self,
+        config: FifoConfig,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        let both = matches!(
+            config.packet,
+            FifoPacketType::Packet3 | FifoPacketType::Packet4
+        );
+        if both && self.config.accel_odr.as_f32() != self.config.gyro_odr.as_f32() {
+            return Err(Error::SensorError(SensorError::InvalidConfig {
+                reason: "accelerometer and gyroscope ODRs differ, so FIFO samples would repeat",
+            }));
+        }
+
+        // `FIFO_MODE` occupies bit 1 and `FIFO_BYPASS` bit 0 in the register
+        let mode = match config.mode {
+            FifoMode::Stream => 0b0000_0000,
+            FifoMode::StopOnFull => 0b0000_0010,
+        };
+        self.write_reg(&Bank0::FIFO_CONFIG1, mode)?;
+
+        // `FIFO_HIRES_EN` occupies bit 3, `FIFO_GYRO_EN` bit 1, and
+        // `FIFO_ACCEL_EN` bit 0 in the register
+        let packet = match config.packet {
+            FifoPacketType::Packet1 => 0b0000_0001,
+            FifoPacketType::Packet2 => 0b0000_0010,
+            FifoPacketType::Packet3 => 0b0000_0011,
+            FifoPacketType::Packet4 => 0b0000_1011,
+        };
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::FIFO_CONFIG5, packet)?;
+
+        self.set_fifo_packet(config.packet)
+    }
+
+    /// Stop writing packets to the FIFO, bypassing it
+    pub fn disable_fifo(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(&Bank0::FIFO_CONFIG1, 0b0000_0001, 0b0000_0001)
+    }
+
+    /// Write timestamps into the FIFO packets which have room for them, with
+    /// the given resolution
+    ///
+    /// The timestamp is a 16-bit count of ticks of the internal clock, so it
+    /// rolls over every 65.536ms at [`TimestampResolution::Us1`] and every
+    /// 1.049s at [`TimestampResolution::Us16`]; packets must be read at
+    /// least that often for the intervals between them to be unambiguous.
+    /// Convert the decoded values with [`FifoRecord::timestamp_us`], passing
+    /// the same resolution. The device has no register holding the current
+    /// timestamp, so it is only available through the FIFO. The setting is
+    /// held in the MREG1 bank, which cannot be written in
+    /// [`crate::PowerMode::Sleep`].
+    pub fn enable_timestamp(
+        &mut self,
+        resolution: TimestampResolution,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        // `TMST_RES` occupies bit 3 and `TMST_EN` bit 0 in the register
+        let res = match resolution {
+            TimestampResolution::Us1 => 0b0000_0000,
+            TimestampResolution::Us16 => 0b0000_1000,
+        };
+        let config = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::TMST_CONFIG1)?;
+        let config = (config & !0b0000_1001) | res | 0b0000_0001;
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::TMST_CONFIG1, config)
+    }
+
+    /// Read every byte held by the FIFO which fits in `buffer`, returning the
+    /// number of bytes read
+    ///
+    /// The number of bytes available is read first, so that no more than the
+    /// FIFO holds is ever read. Decode the data with [`parse_fifo_records`]
+    /// or [`FifoDecimator`]. If `buffer` is too small for the whole FIFO, the
+    /// remaining bytes are left for the next read; size `buffer` as a
+    /// multiple of [`FifoPacketType::size`] to avoid splitting a packet.
+    pub fn read_fifo(&mut self, buffer: &mut [u8]) -> Result<usize, Error<E>> {
+        let available = self.read_fifo_cnt()? as usize;
+        let len = available.min(buffer.len());
+        self.read_fifo_data(&mut buffer[..len])?;
+
+        Ok(len)
+    }
+
+    /// Read as many whole packets from the FIFO as fit in `buffer`, and
+    /// report whether any may have been lost
+    ///
+    /// Unlike [`Icm42670::read_fifo`], a packet which is only partly
+    /// counted, or which would only partly fit in `buffer`, is left in the
+    /// FIFO for the next read, so the data returned always decodes in full.
+    /// The overflow flag is checked as by [`Icm42670::fifo_overflowed`], and
+    /// remains latched until cleared with [`Icm42670::clear_fifo_flags`].
+    /// [`SensorError::FifoNotConfigured`] is returned if the FIFO has not
+    /// been configured.
+    pub fn drain_fifo_packets(&mut self, buffer: &mut [u8]) -> Result<FifoReadStatus, Error<E>> {
+        let packet = self
+            .fifo_packet
+            .ok_or(Error::SensorError(SensorError::FifoNotConfigured))?
+            .size();
+
+        let available = self.read_fifo_cnt()? as usize;
+        let len = available.min(buffer.len()) / packet * packet;
+
+        self.read_fifo_data(&mut buffer[..len])?;
+
+        Ok(FifoReadStatus {
+            bytes_read: len,
+            overflowed: self.fifo_overflowed()?,
+        })
+    }
+
+    /// Read as many whole packets from the FIFO as fit in `buffer`, as
+    /// [`Icm42670::drain_fifo_packets`] does, and decode them into `out`,
+    /// returning the number of records written
+    ///
+    /// The data is scaled using the cached ranges. Every packet is checked
+    /// against the configured format before being decoded, and
+    /// [`SensorError::FifoFormatMismatch`] is returned if any differs, for
+    /// instance if packets written before a reconfiguration remain in the
+    /// FIFO. No more packets are read than fit in both `buffer` and `out`;
+    /// the rest are left in the FIFO.
+    pub fn read_fifo_records(
+        &mut self,
+        buffer: &mut [u8],
+        out: &mut [FifoRecord],
+    ) -> Result<usize, Error<E>> {
+        let packet = self
+            .fifo_packet
+            .ok_or(Error::SensorError(SensorError::FifoNotConfigured))?
+            .size();
+        let len = buffer.len().min(out.len().saturating_mul(packet));
+
+        let status = self.drain_fifo_packets(&mut buffer[..len])?;
+        let buffer = &mut buffer[..status.bytes_read];
+        if self.byte_order == ByteOrder::LittleEndian {
+            swap_fifo_byte_order(buffer);
+        }
+
+        if !Packets::new(buffer).all(|packet| self.is_fifo_format(packet[0])) {
+            return Err(Error::SensorError(SensorError::FifoFormatMismatch));
+        }
+
+        Ok(parse_fifo_records(
+            buffer,
+            out,
+            self.config.accel_range,
+            self.config.gyro_range,
+        ))
+    }
+
+    /// Has the FIFO filled up, and therefore possibly dropped data, since the
+    /// flag was last cleared?
+    ///
+    /// The condition remains latched in the driver until it is acknowledged
+    /// with [`Icm42670::clear_fifo_flags`], so that a gap in the data is never
+    /// missed: an overflow seen by any other read of `INT_STATUS`, such as by
+    /// [`Icm42670::poll_events`], is latched as well. Note that this reads
+    /// `INT_STATUS`, which clears every latched interrupt status bit held in
+    /// that register.
+    pub fn fifo_overflowed(&mut self) -> Result<bool, Error<E>> {
+        // The overflow is latched by every read of the register
+        self.read_status_reg(Bank0::INT_STATUS)?;
+
+        Ok(self.fifo_overflow)
+    }
+
+    /// Does the FIFO hold at least as many bytes as the watermark?
+    ///
+    /// As with [`Icm42670::fifo_overflowed`], this reads `INT_STATUS`, so
+    /// any overflow it reports is latched by the driver rather than lost.
+    pub fn fifo_watermark_reached(&mut self) -> Result<bool, Error<E>> {
+        // `FIFO_THS_INT` occupies bit 2 in the register
+        let status = self.read_status_reg(Bank0::INT_STATUS)?;
+
+        Ok(status & 0b0000_0100 != 0)
+    }
+
+    /// Acknowledge a FIFO overflow, clearing both the driver's latched flag
+    /// and the device's status bit
+    pub fn clear_fifo_flags(&mut self) -> Result<(), Error<E>> {
+        self.read_status_reg(Bank0::INT_STATUS)?;
+        self.fifo_overflow = false;
+
+        Ok(())
+    }
+
+    /// Set the number of bytes the FIFO must hold to raise the watermark
+    /// interrupt
+    ///
+    /// The watermark is 12 bits wide, so [`SensorError::OutOfRange`] is
+    /// returned if `bytes` exceeds 4095.
+    pub fn set_fifo_watermark(&mut self, bytes: u16) -> Result<(), Error<E>> {
+        self.write_fifo_watermark(bytes)?;
+        self.fifo_watermark_packets = None;
+
+        Ok(())
+    }
+
+    /// Set the number of packets the FIFO must hold to raise the watermark
+    /// interrupt
+    ///
+    /// The watermark is converted to bytes using the size of the configured
+    /// packet format, and is recomputed by the driver whenever that format
+    /// changes. [`SensorError::FifoNotConfigured`] is returned if the FIFO
+    /// has not yet been configured, and [`SensorError::OutOfRange`] if the
+    /// packets would not fit in the 2304-byte FIFO.
+    ///
+    /// Route the watermark to an interrupt pin with
+    /// [`Icm42670::route_fifo_watermark`], and once it is asserted check
+    /// [`Icm42670::fifo_watermark_reached`] and read the batch with
+    /// [`Icm42670::read_fifo`].
+    pub fn set_fifo_watermark_packets(&mut self, n: u16) -> Result<(), Error<E>> {
+        let packet = self
+            .fifo_packet
+            .ok_or(Error::SensorError(SensorError::FifoNotConfigured))?;
+
+        self.write_fifo_watermark(watermark_bytes(packet, n)?)?;
+        self.fifo_watermark_packets = Some(n);
+
+        Ok(())
+    }
+
+    /// Record the format of the packets written to the FIFO, recomputing the
+    /// watermark if it was set as a packet count.
+    pub(crate) fn set_fifo_packet(&mut self, packet: FifoPacketType) -> Result<(), Error<E>> {
+        self.fifo_packet = Some(packet);
+
+        if let Some(n) = self.fifo_watermark_packets {
+            self.write_fifo_watermark(watermark_bytes(packet, n)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the FIFO watermark, in bytes.
+    fn write_fifo_watermark(&mut self, bytes: u16) -> Result<(), Error<E>> {
+        if bytes > 0x0FFF {
+            return Err(Error::SensorError(SensorError::OutOfRange));
+        }
+
+        // `FIFO_WM` occupies bits 7:0 of `FIFO_CONFIG2` and bits 3:0 of
+        // `FIFO_CONFIG3`
+        let [high, low] = bytes.to_be_bytes();
+        self.write_regs(&Bank0::FIFO_CONFIG2, &[low, high])
+    }
+}
+
+/// Size in bytes of `n` packets of the given format, which must fit in the
+/// FIFO
+fn watermark_bytes<E>(packet: FifoPacketType, n: u16) -> Result<u16, Error<E>> {
+    n.checked_mul(packet.size() as u16)
+        .filter(|&bytes| bytes <= FIFO_SIZE)
+        .ok_or(Error::SensorError(SensorError::OutOfRange))
+}
@@ -0,0 +1,49 @@
+use accelerometer::vector::F32x3;
+
+/// First-order exponential moving average of a three-axis reading
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Ema {
+    /// Weight given to each new reading; `1.0` disables the filter
+    alpha: f32,
+    /// Most recent filter output, or `None` if no readings have been seen
+    state: Option<F32x3>,
+}
+
+impl Ema {
+    /// A filter which passes every reading through unchanged
+    pub(crate) const fn disabled() -> Self {
+        Self {
+            alpha: 1.0,
+            state: None,
+        }
+    }
+
+    /// Change the weight given to each new reading, clamped to the range
+    /// `0.0..=1.0`, and discard the filter state
+    pub(crate) fn set_alpha(&mut self, alpha: f32) {
+        self.alpha = alpha.clamp(0.0, 1.0);
+        self.state = None;
+    }
+
+    /// Feed a new reading into the filter and return the filtered value
+    ///
+    /// The first reading seeds the filter, so that the output does not ramp
+    /// up from zero.
+    pub(crate) fn update(&mut self, value: F32x3) -> F32x3 {
+        let filtered = match self.state {
+            Some(prev) => {
+                let ema = |new: f32, old: f32| old + self.alpha * (new - old);
+
+                F32x3::new(
+                    ema(value.x, prev.x),
+                    ema(value.y, prev.y),
+                    ema(value.z, prev.z),
+                )
+            }
+            None => value,
+        };
+        self.state = Some(filtered);
+
+        filtered
+    }
+}
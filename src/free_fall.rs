@@ -0,0 +1,88 @@
+use core::fmt::Debug;
+
+use crate::{math, Error, Icm42670, Interface, SensorError};
+
+/// State of the driver's free fall detector
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FreeFall {
+    /// Magnitude below which the device is considered to be falling, in g
+    threshold: f32,
+    /// Consecutive samples below the threshold required to detect a fall
+    min_samples: u16,
+    /// Consecutive samples below the threshold so far
+    count: u16,
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Configure the driver to approximate free fall detection on the host,
+    /// reporting a fall once the acceleration magnitude has stayed below
+    /// `threshold_mg` for at least `min_duration_ms`
+    ///
+    /// This is a software detector, which configures nothing on the device:
+    /// the driver checks the magnitude of each new sample in
+    /// [`Icm42670::freefall_detected`], so no interrupt is raised and the
+    /// device cannot wake the host on a fall. Wake on motion cannot be used
+    /// instead, as it only fires when the acceleration changes by more than
+    /// its threshold, never when every axis falls below one. For a detector
+    /// which runs on the device, see the APEX free fall condition reported by
+    /// [`crate::IntStatus::free_fall`].
+    ///
+    /// The duration is counted in samples at the configured accelerometer
+    /// output data rate, so the detection latency is up to one sample period
+    /// longer than `min_duration_ms`, and longer still if samples are missed
+    /// between calls. The accelerometer must be running.
+    pub fn configure_freefall(
+        &mut self,
+        threshold_mg: u8,
+        min_duration_ms: u16,
+    ) -> Result<(), Error<E>> {
+        let period_us = self
+            .config
+            .effective_accel_period_us()
+            .ok_or(Error::SensorError(SensorError::SensorNotActive {
+                sensor: "accelerometer",
+            }))?;
+        let min_samples = (min_duration_ms as u32 * 1000 + period_us - 1) / period_us;
+
+        self.freefall = Some(FreeFall {
+            threshold: threshold_mg as f32 / 1000.0,
+            min_samples: min_samples.clamp(1, u16::MAX as u32) as u16,
+            count: 0,
+        });
+
+        Ok(())
+    }
+
+    /// Is the device in free fall?
+    ///
+    /// If a new sample is available it is read and checked first, so this
+    /// must be called at least as often as the accelerometer output data rate
+    /// for every sample to be counted. With slower polling, the samples in
+    /// between are skipped, and it takes correspondingly longer to detect a
+    /// fall. [`SensorError::InvalidConfig`] is returned if
+    /// [`Icm42670::configure_freefall`] has not been called.
+    pub fn freefall_detected(&mut self) -> Result<bool, Error<E>> {
+        let mut detector = self
+            .freefall
+            .ok_or(Error::SensorError(SensorError::InvalidConfig {
+                reason: "free fall detection has not been configured",
+            }))?;
+
+        if self.data_ready()? {
+            let (_, accel) = self.read_accel_filtered()?;
+
+            if math::norm(accel.x, accel.y, accel.z) < detector.threshold {
+                detector.count = detector.count.saturating_add(1);
+            } else {
+                detector.count = 0;
+            }
+            self.freefall = Some(detector);
+        }
+
+        Ok(detector.count >= detector.min_samples)
+    }
+}
@@ -0,0 +1,116 @@
+use core::fmt::Debug;
+
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::{
+    register::{Bank0, Mreg1, RegisterBank},
+    Error,
+    Icm42670,
+    Interface,
+};
+
+/// `PIN9_FUNCTION` values selecting the INT2 output and the FSYNC input
+const PIN9_INT2: u8 = 0b0000_0000;
+const PIN9_FSYNC: u8 = 0b0000_0010;
+
+/// The data register into whose least significant bit the FSYNC flag is
+/// written
+///
+/// The flag replaces bit 0 of the selected register in both the data
+/// registers and the FIFO, so that register loses its least significant
+/// bit of resolution while tagging is enabled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FsyncTag {
+    Temp   = 0b001,
+    GyroX  = 0b010,
+    GyroY  = 0b011,
+    GyroZ  = 0b100,
+    AccelX = 0b101,
+    AccelY = 0b110,
+    AccelZ = 0b111,
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Tag samples with external FSYNC pulses
+    ///
+    /// Each sample following a pulse on the FSYNC pin has the least
+    /// significant bit of the `tag` register set, and the time between the
+    /// pulse and that sample is latched; read it with
+    /// [`Icm42670::fsync_delta`]. The FIFO timestamp of the tagged packet is
+    /// also replaced by this delta. The FSYNC input shares pin 9 with INT2,
+    /// so the pin is switched to its FSYNC function, and INT2 is unavailable
+    /// until [`Icm42670::disable_fsync`] switches it back. The configuration
+    /// is held in the MREG1 bank, which cannot be written in
+    /// [`crate::PowerMode::Sleep`].
+    pub fn enable_fsync(
+        &mut self,
+        tag: FsyncTag,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        self.set_pin9_function(delay, PIN9_FSYNC)?;
+
+        // `FSYNC_UI_SEL` occupies bits 6:4 in the register
+        let config = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::FSYNC_CONFIG)?;
+        let config = (config & !0b0111_0000) | (tag as u8) << 4;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::FSYNC_CONFIG, config)?;
+
+        // `TMST_FSYNC_EN` occupies bit 1 in the register
+        let tmst = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::TMST_CONFIG1)?;
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::TMST_CONFIG1,
+            tmst | 0b0000_0010,
+        )
+    }
+
+    /// Stop tagging samples with FSYNC pulses, restoring the full resolution
+    /// of the tagged register and returning pin 9 to INT2
+    pub fn disable_fsync(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        let config = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::FSYNC_CONFIG)?;
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::FSYNC_CONFIG,
+            config & !0b0111_0000,
+        )?;
+
+        let tmst = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::TMST_CONFIG1)?;
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::TMST_CONFIG1,
+            tmst & !0b0000_0010,
+        )?;
+
+        self.set_pin9_function(delay, PIN9_INT2)
+    }
+
+    /// Return the time between the most recent FSYNC pulse and the sample
+    /// which was tagged with it
+    ///
+    /// The delta is counted in units of the timestamp resolution, 1µs by
+    /// default. Both bytes are read in a single transaction.
+    pub fn fsync_delta(&mut self) -> Result<u16, Error<E>> {
+        let mut regs = [0u8; 2];
+        self.read_regs(&Bank0::TMST_FSYNCH, &mut regs)?;
+
+        Ok(u16::from_be_bytes(regs))
+    }
+
+    /// Select the function of pin 9, which is shared by INT2 and FSYNC
+    fn set_pin9_function(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+        function: u8,
+    ) -> Result<(), Error<E>> {
+        // `PIN9_FUNCTION` occupies bits 2:1 in the register
+        let config = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::INTF_CONFIG5)?;
+        let config = (config & !0b0000_0110) | function;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::INTF_CONFIG5, config)
+    }
+}
@@ -0,0 +1,213 @@
+use core::fmt::Debug;
+
+use embedded_hal::{
+    blocking::{i2c, spi},
+    digital::v2::OutputPin,
+};
+
+use crate::Address;
+
+/// A bus over which the device's registers can be read and written
+///
+/// The driver performs every register access through this trait, which is
+/// implemented for I²C by [`I2cInterface`], for 4-wire SPI by
+/// [`SpiInterface`], and for 3-wire SPI by [`Spi3WireInterface`]. Only the
+/// interface which is used is compiled in.
+pub trait Interface {
+    /// Error which may be returned by the bus
+    type Error: Debug;
+
+    /// Value of the `DEVICE_CONFIG` register which the interface requires,
+    /// if it differs from the reset value
+    ///
+    /// The driver writes it before reading any register, both when the
+    /// device is initialized and after a software reset.
+    fn device_config(&self) -> Option<u8> {
+        None
+    }
+
+    /// Fill `buffer` from consecutive registers, starting at `addr`
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Write `data` to the device, where the first byte is the address of the
+    /// first register to be written and the remaining bytes are its values
+    fn write(&mut self, data: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// The I²C interface, addressing the device by its slave address
+#[derive(Debug, Clone, Copy)]
+pub struct I2cInterface<I2C> {
+    /// Underlying I²C peripheral
+    pub(crate) i2c: I2C,
+    /// I²C slave address to use
+    pub(crate) address: Address,
+}
+
+impl<I2C, E> Interface for I2cInterface<I2C>
+where
+    I2C: i2c::Write<Error = E> + i2c::WriteRead<Error = E>,
+    E: Debug,
+{
+    type Error = E;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), E> {
+        self.i2c.write_read(self.address as u8, &[addr], buffer)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), E> {
+        self.i2c.write(self.address as u8, data)
+    }
+}
+
+/// Any type of error which may occur on the SPI interface
+#[derive(Debug)]
+pub enum SpiError<S, P> {
+    /// Some error originating from the SPI peripheral
+    Spi(S),
+    /// Some error originating from the chip select pin
+    ChipSelect(P),
+}
+
+/// The 4-wire SPI interface, selecting the device with a dedicated chip
+/// select pin
+///
+/// The device supports SPI modes 0 and 3, at clock rates of up to 24MHz. For
+/// a device whose data line is shared, see [`Spi3WireInterface`].
+#[derive(Debug, Clone, Copy)]
+pub struct SpiInterface<SPI, CS> {
+    /// Underlying SPI peripheral
+    pub(crate) spi: SPI,
+    /// Active-low chip select pin
+    pub(crate) cs: CS,
+}
+
+impl<SPI, CS, S, P> SpiInterface<SPI, CS>
+where
+    SPI: spi::Transfer<u8, Error = S> + spi::Write<u8, Error = S>,
+    CS: OutputPin<Error = P>,
+{
+    /// Run `f` with the device selected, deselecting it again afterwards even
+    /// if `f` fails
+    fn transaction(
+        &mut self,
+        f: impl FnOnce(&mut SPI) -> Result<(), S>,
+    ) -> Result<(), SpiError<S, P>> {
+        select(&mut self.cs, || f(&mut self.spi))
+    }
+}
+
+impl<SPI, CS, S, P> Interface for SpiInterface<SPI, CS>
+where
+    SPI: spi::Transfer<u8, Error = S> + spi::Write<u8, Error = S>,
+    CS: OutputPin<Error = P>,
+    S: Debug,
+    P: Debug,
+{
+    type Error = SpiError<S, P>;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        // The most significant bit of the address is set for reads
+        self.transaction(|spi| {
+            spi.write(&[addr | 0x80])?;
+            spi.transfer(buffer)?;
+
+            Ok(())
+        })
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        // ...and cleared for writes
+        let (addr, values) = match data.split_first() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+
+        self.transaction(|spi| {
+            spi.write(&[addr & 0x7F])?;
+            spi.write(values)
+        })
+    }
+}
+
+/// A half-duplex SPI bus, whose single data line carries the data in both
+/// directions
+///
+/// `embedded-hal` 0.2 has no trait for such a bus, so this must be
+/// implemented for the SPI peripheral, typically by switching it between
+/// transmitting and receiving on its bidirectional data line.
+pub trait HalfDuplexSpi {
+    /// Error which may be returned by the bus
+    type Error: Debug;
+
+    /// Clock out `data`, driving the data line
+    fn write(&mut self, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Clock in enough bytes to fill `buffer`, with the data line released
+    /// so that the device can drive it
+    fn read(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+/// The 3-wire SPI interface, in which the device shares a single data line
+/// for both directions, selecting it with a dedicated chip select pin
+///
+/// The device starts in 4-wire mode, in which it still accepts writes on the
+/// shared line, so the driver selects 3-wire mode before reading any
+/// register. A software reset returns the device to 4-wire mode, so use
+/// [`crate::Icm42670::soft_reset_blocking`], which selects 3-wire mode again,
+/// rather than [`crate::Icm42670::soft_reset`].
+#[derive(Debug, Clone, Copy)]
+pub struct Spi3WireInterface<SPI, CS> {
+    /// Underlying half-duplex SPI peripheral
+    pub(crate) spi: SPI,
+    /// Active-low chip select pin
+    pub(crate) cs: CS,
+}
+
+impl<SPI, CS, S, P> Interface for Spi3WireInterface<SPI, CS>
+where
+    SPI: HalfDuplexSpi<Error = S>,
+    CS: OutputPin<Error = P>,
+    S: Debug,
+    P: Debug,
+{
+    type Error = SpiError<S, P>;
+
+    fn device_config(&self) -> Option<u8> {
+        // `SPI_AP_4WIRE` occupies bit 2 in the register, and is cleared to
+        // select 3-wire mode
+        Some(0b0000_0000)
+    }
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        // The data line is turned around after the address has been written
+        select(&mut self.cs, || {
+            self.spi.write(&[addr | 0x80])?;
+            self.spi.read(buffer)
+        })
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        let (addr, values) = match data.split_first() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+
+        select(&mut self.cs, || {
+            self.spi.write(&[addr & 0x7F])?;
+            self.spi.write(values)
+        })
+    }
+}
+
+/// Run `f` with the device selected by `cs`, deselecting it again afterwards
+/// even if `f` fails
+fn select<CS, S, P>(cs: &mut CS, f: impl FnOnce() -> Result<(), S>) -> Result<(), SpiError<S, P>>
+where
+    CS: OutputPin<Error = P>,
+{
+    cs.set_low().map_err(SpiError::ChipSelect)?;
+    let result = f().map_err(SpiError::Spi);
+    cs.set_high().map_err(SpiError::ChipSelect)?;
+
+    result
+}
@@ -0,0 +1,751 @@
+use core::{
+    fmt::Debug,
+    ops::{BitOr, BitOrAssign},
+};
+
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::{
+    register::{Bank0, Mreg1, RegisterBank},
+    Error,
+    Icm42670,
+    Interface,
+    SensorError,
+};
+
+/// Sensor axes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// A single interrupt condition reported by the device
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntEvent {
+    /// New sensor data is available
+    DataReady,
+    /// Self-test has completed
+    SelfTestDone,
+    /// An FSYNC pulse was detected
+    Fsync,
+    /// The PLL is ready
+    PllReady,
+    /// The device has finished resetting
+    ResetDone,
+    /// The FIFO has reached its watermark
+    FifoThreshold,
+    /// The FIFO is full
+    FifoFull,
+    /// The automatic gain control is ready
+    AgcReady,
+    /// Wake on motion was triggered by the given axis
+    Wom(Axis),
+    /// Significant motion was detected
+    SignificantMotion,
+    /// A step was detected
+    Step,
+    /// The step counter overflowed
+    StepCountOverflow,
+    /// A tilt was detected
+    Tilt,
+    /// A free fall was detected
+    FreeFall,
+    /// A low-g condition was detected
+    LowG,
+}
+
+impl IntEvent {
+    /// Is this event raised by one of the APEX features?
+    pub fn is_apex(&self) -> bool {
+        use IntEvent::*;
+
+        matches!(
+            self,
+            SignificantMotion | Step | StepCountOverflow | Tilt | FreeFall | LowG
+        )
+    }
+}
+
+/// Axes which triggered a wake on motion interrupt
+///
+/// The device does not latch the accelerometer values which caused the
+/// interrupt, only which axes exceeded their thresholds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WomStatus {
+    pub x: bool,
+    pub y: bool,
+    pub z: bool,
+}
+
+impl WomStatus {
+    /// Decode the `INT_STATUS2` register
+    pub(crate) fn from_register(reg: u8) -> Self {
+        Self {
+            x: reg & 0b0000_0001 != 0,
+            y: reg & 0b0000_0010 != 0,
+            z: reg & 0b0000_0100 != 0,
+        }
+    }
+
+    /// Was wake on motion triggered by any axis?
+    pub fn any(&self) -> bool {
+        self.x || self.y || self.z
+    }
+}
+
+/// Decoded contents of the interrupt status registers
+///
+/// Reading the status registers clears any latched interrupt bits, so each
+/// condition is only reported once. Each register is cleared only by reading
+/// it, not by reading the others:
+///
+/// - `INT_STATUS_DRDY` holds `data_ready`.
+/// - `INT_STATUS` holds `self_test_done` through `agc_ready`.
+/// - `INT_STATUS2` holds the wake on motion axes and `significant_motion`.
+/// - `INT_STATUS3` holds `step` through `low_g`.
+///
+/// [`Icm42670::read_int_status`] reads all four in a single transaction, so
+/// that every condition is captured at once. The same set of conditions is
+/// used to select which of them assert an interrupt pin; see
+/// [`Icm42670::set_int_sources`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IntStatus {
+    pub data_ready: bool,
+    pub self_test_done: bool,
+    pub fsync: bool,
+    pub pll_ready: bool,
+    pub reset_done: bool,
+    pub fifo_threshold: bool,
+    pub fifo_full: bool,
+    pub agc_ready: bool,
+    pub wom_x: bool,
+    pub wom_y: bool,
+    pub wom_z: bool,
+    pub significant_motion: bool,
+    pub step: bool,
+    pub step_count_overflow: bool,
+    pub tilt: bool,
+    pub free_fall: bool,
+    pub low_g: bool,
+}
+
+impl IntStatus {
+    /// Decode the `INT_STATUS_DRDY`, `INT_STATUS`, `INT_STATUS2`, and
+    /// `INT_STATUS3` registers, in that order
+    pub(crate) fn from_registers(regs: [u8; 4]) -> Self {
+        let bit = |reg: u8, n: u8| reg & (1 << n) != 0;
+        let [drdy, status, status2, status3] = regs;
+
+        Self {
+            data_ready: bit(drdy, 0),
+            self_test_done: bit(status, 7),
+            fsync: bit(status, 6),
+            pll_ready: bit(status, 5),
+            reset_done: bit(status, 4),
+            fifo_threshold: bit(status, 2),
+            fifo_full: bit(status, 1),
+            agc_ready: bit(status, 0),
+            wom_x: bit(status2, 0),
+            wom_y: bit(status2, 1),
+            wom_z: bit(status2, 2),
+            significant_motion: bit(status2, 3),
+            step: bit(status3, 5),
+            step_count_overflow: bit(status3, 4),
+            tilt: bit(status3, 3),
+            free_fall: bit(status3, 2),
+            low_g: bit(status3, 1),
+        }
+    }
+
+    /// The wake on motion status of each axis
+    pub fn wom(&self) -> WomStatus {
+        WomStatus {
+            x: self.wom_x,
+            y: self.wom_y,
+            z: self.wom_z,
+        }
+    }
+
+    /// Is no interrupt condition active?
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Call `f` once for each active interrupt condition
+    pub fn dispatch<F>(&self, mut f: F)
+    where
+        F: FnMut(IntEvent),
+    {
+        let events = [
+            (self.data_ready, IntEvent::DataReady),
+            (self.self_test_done, IntEvent::SelfTestDone),
+            (self.fsync, IntEvent::Fsync),
+            (self.pll_ready, IntEvent::PllReady),
+            (self.reset_done, IntEvent::ResetDone),
+            (self.fifo_threshold, IntEvent::FifoThreshold),
+            (self.fifo_full, IntEvent::FifoFull),
+            (self.agc_ready, IntEvent::AgcReady),
+            (self.wom_x, IntEvent::Wom(Axis::X)),
+            (self.wom_y, IntEvent::Wom(Axis::Y)),
+            (self.wom_z, IntEvent::Wom(Axis::Z)),
+            (self.significant_motion, IntEvent::SignificantMotion),
+            (self.step, IntEvent::Step),
+            (self.step_count_overflow, IntEvent::StepCountOverflow),
+            (self.tilt, IntEvent::Tilt),
+            (self.free_fall, IntEvent::FreeFall),
+            (self.low_g, IntEvent::LowG),
+        ];
+
+        for (active, event) in events {
+            if active {
+                f(event);
+            }
+        }
+    }
+}
+
+impl BitOr for IntStatus {
+    type Output = Self;
+
+    /// The conditions which are active in either set
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            data_ready: self.data_ready || rhs.data_ready,
+            self_test_done: self.self_test_done || rhs.self_test_done,
+            fsync: self.fsync || rhs.fsync,
+            pll_ready: self.pll_ready || rhs.pll_ready,
+            reset_done: self.reset_done || rhs.reset_done,
+            fifo_threshold: self.fifo_threshold || rhs.fifo_threshold,
+            fifo_full: self.fifo_full || rhs.fifo_full,
+            agc_ready: self.agc_ready || rhs.agc_ready,
+            wom_x: self.wom_x || rhs.wom_x,
+            wom_y: self.wom_y || rhs.wom_y,
+            wom_z: self.wom_z || rhs.wom_z,
+            significant_motion: self.significant_motion || rhs.significant_motion,
+            step: self.step || rhs.step,
+            step_count_overflow: self.step_count_overflow || rhs.step_count_overflow,
+            tilt: self.tilt || rhs.tilt,
+            free_fall: self.free_fall || rhs.free_fall,
+            low_g: self.low_g || rhs.low_g,
+        }
+    }
+}
+
+impl BitOrAssign for IntStatus {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+/// Duration of the pulse generated on an interrupt pin in pulsed mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntPulseDuration {
+    /// 100µs
+    Us100,
+    /// 8µs
+    Us8,
+}
+
+impl Default for IntPulseDuration {
+    fn default() -> Self {
+        IntPulseDuration::Us100
+    }
+}
+
+/// Minimum time for which an interrupt pin is held de-asserted between
+/// consecutive interrupts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntDeassertTime {
+    /// 100µs
+    Us100,
+    /// No minimum de-assertion time
+    Disabled,
+}
+
+impl Default for IntDeassertTime {
+    fn default() -> Self {
+        IntDeassertTime::Us100
+    }
+}
+
+/// What clears the data ready, FIFO watermark, and FIFO full conditions
+///
+/// The other conditions are always cleared by reading their status register.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntClearMode {
+    /// Reading the status register
+    OnStatusRead        = 0b00,
+    /// Reading the sensor data registers, or a byte from the FIFO for the
+    /// FIFO conditions
+    OnDataRead          = 0b10,
+    /// Reading both the status register and the data
+    OnStatusAndDataRead = 0b11,
+}
+
+impl Default for IntClearMode {
+    fn default() -> Self {
+        IntClearMode::OnStatusRead
+    }
+}
+
+/// Interrupt pins
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntPin {
+    Int1,
+    Int2,
+}
+
+/// Logic level of an interrupt pin while asserted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntPolarity {
+    ActiveLow,
+    ActiveHigh,
+}
+
+impl Default for IntPolarity {
+    fn default() -> Self {
+        IntPolarity::ActiveLow
+    }
+}
+
+/// Output circuit driving an interrupt pin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntDrive {
+    OpenDrain,
+    PushPull,
+}
+
+impl Default for IntDrive {
+    fn default() -> Self {
+        IntDrive::OpenDrain
+    }
+}
+
+/// Whether an interrupt pin pulses or remains asserted until the interrupt
+/// status is read
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntMode {
+    Pulsed,
+    Latched,
+}
+
+impl Default for IntMode {
+    fn default() -> Self {
+        IntMode::Pulsed
+    }
+}
+
+/// Electrical configuration of an interrupt pin
+///
+/// The pulse duration and de-assertion time are shared by both pins, so
+/// configuring either pin changes them for both. Slow interrupt inputs may
+/// miss the 8µs pulse, so the 100µs default should be preferred unless
+/// interrupts are serviced quickly. Both durations are shorter than the
+/// period of the fastest supported output data rate, so every combination is
+/// valid.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IntPinConfig {
+    /// Logic level while asserted
+    pub polarity: IntPolarity,
+    /// Output circuit
+    pub drive: IntDrive,
+    /// Pulsed or latched operation
+    pub mode: IntMode,
+    /// Duration of the pulse generated in pulsed mode
+    pub pulse_duration: IntPulseDuration,
+    /// Minimum de-assertion time between interrupts
+    pub deassert_time: IntDeassertTime,
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Read and decode all of the interrupt status registers
+    ///
+    /// The registers are read in a single transaction, so this is the way to
+    /// take a consistent snapshot of every pending condition, for example
+    /// from an interrupt handler. This clears every latched interrupt status
+    /// bit; see [`IntStatus`].
+    pub fn read_int_status(&mut self) -> Result<IntStatus, Error<E>> {
+        let mut regs = [0u8; 4];
+        self.read_regs(&Bank0::INT_STATUS_DRDY, &mut regs)?;
+
+        let status = IntStatus::from_registers(regs);
+        self.latch_status(status);
+
+        Ok(status)
+    }
+
+    /// Return every interrupt condition which has occurred since the previous
+    /// call
+    ///
+    /// Reading the status registers clears them, so the driver accumulates
+    /// every condition it observes, including those seen by other methods
+    /// such as [`Icm42670::wom_triggered`] or [`Icm42670::fifo_overflowed`].
+    /// Calling this periodically therefore never misses a transient event,
+    /// even from a slow main loop. Conditions are reported at most once per
+    /// occurrence, although several occurrences between calls are merged.
+    pub fn poll_events(&mut self) -> Result<IntStatus, Error<E>> {
+        self.read_int_status()?;
+
+        let events = self.pending_events;
+        self.pending_events = IntStatus::default();
+
+        Ok(events)
+    }
+
+    /// Read which axes, if any, have triggered a wake on motion interrupt
+    ///
+    /// This clears the latched wake on motion and significant motion status
+    /// bits.
+    pub fn wom_triggered(&mut self) -> Result<WomStatus, Error<E>> {
+        let status = self.read_status_reg(Bank0::INT_STATUS2)?;
+
+        Ok(WomStatus::from_register(status))
+    }
+
+    /// Configure the electrical behaviour of an interrupt pin
+    ///
+    /// See [`IntPinConfig`] regarding the settings shared by both pins.
+    pub fn set_int_pin_config(
+        &mut self,
+        pin: IntPin,
+        config: IntPinConfig,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        // `INTx_MODE`, `INTx_DRIVE_CIRCUIT`, and `INTx_POLARITY` occupy bits
+        // 2:0 of the register for INT1, and bits 5:3 for INT2
+        let mut bits = 0;
+        if config.mode == IntMode::Latched {
+            bits |= 0b100;
+        }
+        if config.drive == IntDrive::PushPull {
+            bits |= 0b010;
+        }
+        if config.polarity == IntPolarity::ActiveHigh {
+            bits |= 0b001;
+        }
+        let shift = int_config_shift(pin);
+        self.update_reg(&Bank0::INT_CONFIG, bits << shift, 0b111 << shift)?;
+
+        // `INT_TPULSE_DURATION` occupies bit 6 in the register
+        // `INT_TDEASSERT_DISABLE` occupies bit 5 in the register
+        // `INT_ASYNC_RESET` occupies bit 4 in the register, and must be cleared
+        // for the interrupt pins to operate correctly
+        let mut value = 0;
+        if config.pulse_duration == IntPulseDuration::Us8 {
+            value |= 0b0100_0000;
+        }
+        if config.deassert_time == IntDeassertTime::Disabled {
+            value |= 0b0010_0000;
+        }
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::INT_CONFIG1, value)
+    }
+
+    /// Select what clears the data ready, FIFO watermark, and FIFO full
+    /// conditions, and so de-asserts a latched interrupt pin
+    ///
+    /// By default, [`IntClearMode::OnStatusRead`], a latched pin stays
+    /// asserted until the status is read, for example with
+    /// [`Icm42670::poll_events`], even after the data has been. With
+    /// [`IntClearMode::OnDataRead`] the FIFO watermark condition is cleared
+    /// by reading the first byte from the FIFO, so it is re-raised as soon as
+    /// the FIFO count next reaches the watermark, without any further status
+    /// read. The setting is held in the MREG1 bank, which cannot be written
+    /// in [`crate::PowerMode::Sleep`].
+    pub fn set_interrupt_clear_mode(
+        &mut self,
+        mode: IntClearMode,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        // `UI_DRDY_INT_CLEAR` occupies bits 5:4, `FIFO_THS_INT_CLEAR` bits 3:2,
+        // and `FIFO_FULL_INT_CLEAR` bits 1:0 in the register
+        let mode = mode as u8;
+        let value = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::INT_CONFIG0)?;
+        let value = (value & !0b0011_1111) | mode << 4 | mode << 2 | mode;
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::INT_CONFIG0, value)
+    }
+
+    /// Route the given interrupt conditions to a pin
+    ///
+    /// Every condition set in `sources` asserts the pin, and every other
+    /// condition is disconnected from it. The APEX conditions (step, step
+    /// count overflow, tilt, free fall, and low-g) are routed by the
+    /// `INT_SOURCE6` and `INT_SOURCE7` registers of the MREG1 bank, so `delay`
+    /// is required and the internal clock must be running.
+    pub fn set_int_sources(
+        &mut self,
+        pin: IntPin,
+        sources: IntStatus,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        self.set_int_sources_bank0(pin, sources)?;
+
+        let reg = match pin {
+            IntPin::Int1 => Mreg1::INT_SOURCE6,
+            IntPin::Int2 => Mreg1::INT_SOURCE7,
+        };
+        let bit = |enabled: bool, n: u8| if enabled { 1 << n } else { 0 };
+
+        // `FF_INTx_EN` occupies bit 7 and `LOWG_INTx_EN` bit 6, while the
+        // step, step count overflow, and tilt enable bits occupy the same
+        // positions as the corresponding bits of `INT_STATUS3`; bits 2:0 are
+        // reserved, and are preserved
+        let apex = bit(sources.free_fall, 7)
+            | bit(sources.low_g, 6)
+            | bit(sources.step, 5)
+            | bit(sources.step_count_overflow, 4)
+            | bit(sources.tilt, 3);
+        let value = self.read_mreg(delay, RegisterBank::MReg1, &reg)?;
+        let value = (value & 0b0000_0111) | apex;
+
+        self.write_mreg(delay, RegisterBank::MReg1, &reg, value)
+    }
+
+    /// Route the given conditions in User Bank 0 to a pin, disconnecting
+    /// every other condition in User Bank 0 from it
+    fn set_int_sources_bank0(&mut self, pin: IntPin, sources: IntStatus) -> Result<(), Error<E>> {
+        let bit = |enabled: bool, n: u8| if enabled { 1 << n } else { 0 };
+
+        // The enable bits occupy the same positions as the corresponding
+        // bits of `INT_STATUS` and `INT_STATUS2`, except for `DRDY_INTx_EN`,
+        // which occupies bit 3
+        let source0 = bit(sources.self_test_done, 7)
+            | bit(sources.fsync, 6)
+            | bit(sources.pll_ready, 5)
+            | bit(sources.reset_done, 4)
+            | bit(sources.data_ready, 3)
+            | bit(sources.fifo_threshold, 2)
+            | bit(sources.fifo_full, 1)
+            | bit(sources.agc_ready, 0);
+        let source1 = bit(sources.significant_motion, 3)
+            | bit(sources.wom_z, 2)
+            | bit(sources.wom_y, 1)
+            | bit(sources.wom_x, 0);
+
+        match pin {
+            IntPin::Int1 => {
+                self.write_reg(&Bank0::INT_SOURCE0, source0)?;
+                self.write_reg(&Bank0::INT_SOURCE1, source1)
+            }
+            IntPin::Int2 => {
+                self.write_reg(&Bank0::INT_SOURCE3, source0)?;
+                self.write_reg(&Bank0::INT_SOURCE4, source1)
+            }
+        }
+    }
+
+    /// Read back which interrupt conditions are routed to a pin
+    ///
+    /// The APEX conditions are routed by the `INT_SOURCE6` and `INT_SOURCE7`
+    /// registers of the MREG1 bank, which are read as well, so `delay` is
+    /// required and the internal clock must be running.
+    pub fn int_sources(
+        &mut self,
+        pin: IntPin,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<IntStatus, Error<E>> {
+        let reg = match pin {
+            IntPin::Int1 => Mreg1::INT_SOURCE6,
+            IntPin::Int2 => Mreg1::INT_SOURCE7,
+        };
+        let apex = self.read_mreg(delay, RegisterBank::MReg1, &reg)?;
+        let bit = |n: u8| apex & (1 << n) != 0;
+
+        // `FF_INTx_EN` occupies bit 7 and `LOWG_INTx_EN` bit 6, while the
+        // step, step count overflow, and tilt enable bits occupy the same
+        // positions as the corresponding bits of `INT_STATUS3`
+        Ok(IntStatus {
+            free_fall: bit(7),
+            low_g: bit(6),
+            step: bit(5),
+            step_count_overflow: bit(4),
+            tilt: bit(3),
+            ..self.int_sources_bank0(pin)?
+        })
+    }
+
+    /// Read back which of the conditions in User Bank 0 are routed to a pin.
+    fn int_sources_bank0(&mut self, pin: IntPin) -> Result<IntStatus, Error<E>> {
+        let (source0, source1) = match pin {
+            IntPin::Int1 => (
+                self.read_reg(&Bank0::INT_SOURCE0)?,
+                self.read_reg(&Bank0::INT_SOURCE1)?,
+            ),
+            IntPin::Int2 => (
+                self.read_reg(&Bank0::INT_SOURCE3)?,
+                self.read_reg(&Bank0::INT_SOURCE4)?,
+            ),
+        };
+        let bit = |reg: u8, n: u8| reg & (1 << n) != 0;
+
+        Ok(IntStatus {
+            self_test_done: bit(source0, 7),
+            fsync: bit(source0, 6),
+            pll_ready: bit(source0, 5),
+            reset_done: bit(source0, 4),
+            data_ready: bit(source0, 3),
+            fifo_threshold: bit(source0, 2),
+            fifo_full: bit(source0, 1),
+            agc_ready: bit(source0, 0),
+            significant_motion: bit(source1, 3),
+            wom_z: bit(source1, 2),
+            wom_y: bit(source1, 1),
+            wom_x: bit(source1, 0),
+            ..IntStatus::default()
+        })
+    }
+
+    /// Route the given conditions to a pin, in addition to any which are
+    /// already routed to it
+    ///
+    /// Only the conditions in User Bank 0 can be routed this way, as it
+    /// requires no `delay`; the APEX conditions are routed with
+    /// [`Icm42670::set_int_sources`], and [`SensorError::InvalidConfig`] is
+    /// returned if any are set.
+    pub fn route_int_sources(&mut self, pin: IntPin, sources: IntStatus) -> Result<(), Error<E>> {
+        if sources.step
+            || sources.step_count_overflow
+            || sources.tilt
+            || sources.free_fall
+            || sources.low_g
+        {
+            return Err(Error::SensorError(SensorError::InvalidConfig {
+                reason: "APEX interrupt sources are routed with set_int_sources",
+            }));
+        }
+
+        let current = self.int_sources_bank0(pin)?;
+
+        self.set_int_sources_bank0(pin, current | sources)
+    }
+
+    /// Route the data ready condition to a pin, in addition to any which are
+    /// already routed to it
+    pub fn route_data_ready(&mut self, pin: IntPin) -> Result<(), Error<E>> {
+        self.route_int_sources(
+            pin,
+            IntStatus {
+                data_ready: true,
+                ..IntStatus::default()
+            },
+        )
+    }
+
+    /// Route the FIFO watermark condition to a pin, in addition to any which
+    /// are already routed to it
+    pub fn route_fifo_watermark(&mut self, pin: IntPin) -> Result<(), Error<E>> {
+        self.route_int_sources(
+            pin,
+            IntStatus {
+                fifo_threshold: true,
+                ..IntStatus::default()
+            },
+        )
+    }
+
+    /// Route the wake on motion conditions of every axis to a pin, in
+    /// addition to any which are already routed to it
+    pub fn route_wom(&mut self, pin: IntPin) -> Result<(), Error<E>> {
+        self.route_int_sources(
+            pin,
+            IntStatus {
+                wom_x: true,
+                wom_y: true,
+                wom_z: true,
+                ..IntStatus::default()
+            },
+        )
+    }
+
+    /// Configure an interrupt pin and route the given conditions to it
+    ///
+    /// This combines [`Icm42670::set_int_pin_config`] and
+    /// [`Icm42670::set_int_sources`], so a pin's complete setup can be
+    /// expressed in one call, in the order in which it must be applied: the
+    /// pin is configured before any condition can assert it.
+    pub fn configure_interrupt(
+        &mut self,
+        pin: IntPin,
+        sources: IntStatus,
+        config: IntPinConfig,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        self.set_int_pin_config(pin, config, delay)?;
+        self.set_int_sources(pin, sources, delay)
+    }
+
+    /// Read back the electrical configuration of an interrupt pin
+    pub fn read_int_config(
+        &mut self,
+        pin: IntPin,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<IntPinConfig, Error<E>> {
+        let bits = self.read_reg(&Bank0::INT_CONFIG)? >> int_config_shift(pin);
+        let timing = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::INT_CONFIG1)?;
+
+        Ok(IntPinConfig {
+            polarity: if bits & 0b001 != 0 {
+                IntPolarity::ActiveHigh
+            } else {
+                IntPolarity::ActiveLow
+            },
+            drive: if bits & 0b010 != 0 {
+                IntDrive::PushPull
+            } else {
+                IntDrive::OpenDrain
+            },
+            mode: if bits & 0b100 != 0 {
+                IntMode::Latched
+            } else {
+                IntMode::Pulsed
+            },
+            pulse_duration: if timing & 0b0100_0000 != 0 {
+                IntPulseDuration::Us8
+            } else {
+                IntPulseDuration::Us100
+            },
+            deassert_time: if timing & 0b0010_0000 != 0 {
+                IntDeassertTime::Disabled
+            } else {
+                IntDeassertTime::Us100
+            },
+        })
+    }
+
+    /// Read a single interrupt status register, accumulating its conditions
+    /// for [`Icm42670::poll_events`]
+    pub(crate) fn read_status_reg(&mut self, reg: Bank0) -> Result<u8, Error<E>> {
+        let value = self.read_reg(&reg)?;
+
+        let mut regs = [0u8; 4];
+        regs[(reg as u8 - Bank0::INT_STATUS_DRDY as u8) as usize] = value;
+        self.latch_status(IntStatus::from_registers(regs));
+
+        Ok(value)
+    }
+
+    /// Record conditions read from the interrupt status registers, which
+    /// reading has cleared: they are accumulated for
+    /// [`Icm42670::poll_events`], and a FIFO overflow is latched until
+    /// [`Icm42670::clear_fifo_flags`]. Every read of the registers must pass
+    /// through here.
+    pub(crate) fn latch_status(&mut self, status: IntStatus) {
+        self.pending_events |= status;
+        self.fifo_overflow |= status.fifo_full;
+    }
+}
+
+/// Offset of the pin's fields within the `INT_CONFIG` register
+fn int_config_shift(pin: IntPin) -> u8 {
+    match pin {
+        IntPin::Int1 => 0,
+        IntPin::Int2 => 3,
+    }
+}
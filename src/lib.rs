@@ -2,16 +2,25 @@
 //!
 //! The ICM-42670 combines a 3-axis accelerometer with a 3-axis gyroscope into a
 //! single package. It has a configurable host interface which supports I²C,
-//! SPI, and I3C communications. Presently this driver only supports using the
-//! I²C interface.
+//! SPI, and I3C communications. This driver supports the I²C interface, using
+//! [`Icm42670::new`], the 4-wire SPI interface, using [`Icm42670::new_spi`],
+//! and the 3-wire SPI interface, using [`Icm42670::new_spi_3wire`].
 //!
 //! For additional information about this device please refer to the
 //! [datasheet].
 //!
+//! The driver implements the [`Accelerometer`] and [`RawAccelerometer`]
+//! traits from the [accelerometer] crate, so it can be used by any code which
+//! is generic over them. These traits are always available, and can be
+//! brought into scope using the [`prelude`]. There is no equivalent
+//! ecosystem trait for gyroscopes, so the gyroscope data is only available
+//! through the inherent methods.
+//!
+//! [accelerometer]: https://docs.rs/accelerometer/latest/accelerometer/
 //! [embedded-hal]: https://docs.rs/embedded-hal/latest/embedded_hal/
 //! [datasheet]: https://3cfeqx1hf82y3xcoull08ihx-wpengine.netdna-ssl.com/wp-content/uploads/2021/07/DS-000451-ICM-42670-P-v1.0.pdf
 
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 use core::f32::consts::PI;
 use core::fmt::Debug;
@@ -22,24 +31,70 @@ use accelerometer::{
     vector::{F32x3, I16x3},
     Accelerometer, RawAccelerometer,
 };
-use embedded_hal::blocking::{
-    delay::DelayUs,
-    i2c::{Write, WriteRead},
+use embedded_hal::{
+    blocking::{
+        delay::DelayUs,
+        i2c::{Write, WriteRead},
+        spi,
+    },
+    digital::v2::OutputPin,
 };
 
-use crate::{
-    config::Bitfield,
-    error::SensorError,
-    register::{Bank0, Mreg1, Register, RegisterBank},
-};
 pub use crate::{
-    config::{AccelBw, AccelOdr, AccelRange, Address, GyroBw, GyroOdr, GyroRange, PowerMode},
-    error::Error,
+    apex::{Activity, ApexConfig, ApexData, PedometerMode, SmdSensitivity},
+    calibration::{Bias, BiasQuality},
+    config::{
+        AccelAvg, AccelBw, AccelConfig, AccelLpClock, AccelMode, AccelOdr, AccelRange, Address,
+        ByteOrder, ClockSource, Config, GyroBw, GyroConfig, GyroMode, GyroOdr, GyroRange, Hz,
+        PowerMode,
+    },
+    diagnostics::{Capabilities, REGISTER_DUMP_LEN},
+    error::{Error, SensorError},
+    event_log::EventLog,
+    fifo::{
+        parse_fifo_into, parse_fifo_records, swap_fifo_byte_order, FifoConfig, FifoDecimator,
+        FifoHeader, FifoMode, FifoReadStatus, FifoRecord, TimestampResolution,
+    },
+    fsync::FsyncTag,
+    interface::{HalfDuplexSpi, I2cInterface, Interface, Spi3WireInterface, SpiError, SpiInterface},
+    interrupts::{
+        Axis, IntClearMode, IntDeassertTime, IntDrive, IntEvent, IntMode, IntPin, IntPinConfig,
+        IntPolarity, IntPulseDuration, IntStatus, WomStatus,
+    },
+    register::RegisterBank,
+    sample::{Inclination, Sample, Sample6Dof},
+    self_test::SelfTestResult,
+    units::{Acceleration, AngularVelocity},
+    wom::{WomCompare, WomConfig, WomMode},
+};
+use crate::{
+    config::{apply_bitfield, extract_bitfield, Bitfield},
+    filter::Ema,
+    free_fall::FreeFall,
+    register::{Bank0, Mreg1, Register},
+    units::STANDARD_GRAVITY,
 };
 
+mod apex;
+mod calibration;
 pub mod config;
+mod diagnostics;
 mod error;
+mod event_log;
+mod fifo;
+mod filter;
+mod free_fall;
+mod fsync;
+mod interface;
+mod interrupts;
+mod math;
+#[cfg(feature = "mock")]
+pub mod mock;
 mod register;
+mod sample;
+mod self_test;
+mod units;
+mod wom;
 
 /// Re-export any traits which may be required by end users
 pub mod prelude {
@@ -49,50 +104,113 @@ pub mod prelude {
     };
 }
 
-const GRAVITY: f32 = 9.81;
-
 /// ICM-42670 driver
 #[derive(Debug, Clone, Copy)]
-pub struct Icm42670<I2C> {
-    /// Underlying I²C peripheral
-    i2c: I2C,
-    /// I²C slave address to use
-    address: Address,
+pub struct Icm42670<DI> {
+    /// Underlying bus interface
+    iface: DI,
+    /// Configuration most recently written to the device
+    config: Config,
+    /// Configuration to restore when waking from sleep
+    sleep_config: Option<Config>,
+    /// Normalized gyro readings below this magnitude are reported as zero
+    gyro_deadband: f32,
+    /// Offsets subtracted from normalized readings, in addition to any
+    /// programmed into the device
+    software_bias: Bias,
+    /// Change of the software gyro bias with temperature, in deg/sec/°C, and
+    /// the temperature at which the bias was measured, if compensated
+    gyro_temp_coeff: Option<(F32x3, f32)>,
+    /// Most recently read raw accelerometer data
+    last_accel_raw: Option<I16x3>,
+    /// Did the most recent accelerometer read repeat the one before it?
+    accel_repeated: bool,
+    /// Number of consecutive accelerometer reads identical to the one before
+    accel_repeats: u16,
+    /// Most recently read raw gyro data
+    last_gyro_raw: Option<I16x3>,
+    /// Number of consecutive gyro reads identical to the one before
+    gyro_repeats: u16,
+    /// Number of identical consecutive reads after which the data is
+    /// reported as stuck, if stuck detection is enabled
+    stuck_threshold: Option<u16>,
+    /// Has the DMP been successfully initialized?
+    dmp_initialized: bool,
+    /// Read back configuration registers after writing them?
+    verify_writes: bool,
+    /// Has the FIFO overflowed since the flag was last cleared?
+    fifo_overflow: bool,
+    /// Smoothing applied to normalized accelerometer readings
+    accel_ema: Ema,
+    /// Smoothing applied to normalized gyro readings
+    gyro_ema: Ema,
+    /// Format of the packets written to the FIFO, if it has been configured
+    fifo_packet: Option<FifoPacketType>,
+    /// FIFO watermark in packets, if it was set as a packet count
+    fifo_watermark_packets: Option<u16>,
+    /// Interrupt conditions observed since the last call to `poll_events`
+    pending_events: IntStatus,
+    /// Maximum number of bytes transferred in a single bus transaction
+    max_transaction_len: Option<usize>,
+    /// Number of times a failed bus transaction is retried
+    bus_retries: u8,
+    /// Step count at the previous call to `take_step_count`
+    step_count_taken: u16,
+    /// Free fall detector, if it has been configured
+    freefall: Option<FreeFall>,
+    /// Byte order of the sensor data and the FIFO count
+    byte_order: ByteOrder,
+    /// Number of measurements averaged into each low-power accelerometer
+    /// sample
+    accel_avg: AccelAvg,
 }
 
-impl<I2C, E> Icm42670<I2C>
+impl<I2C, E> Icm42670<I2cInterface<I2C>>
 where
     I2C: Write<Error = E> + WriteRead<Error = E>,
     E: Debug,
 {
-    /// Unique device identifiers for the ICM-42607 and ICM-42670
-    ///
-    /// The ICM-42607 is the mass-production version of the ICM-42670, and
-    /// differs only by part number and device ID.
-    pub const DEVICE_IDS: [u8; 2] = [
-        0x60, // ICM-42607
-        0x67, // ICM-42670
-    ];
-
     /// Instantiate a new instance of the driver and initialize the device
     pub fn new(i2c: I2C, address: Address) -> Result<Self, Error<E>> {
-        let mut me = Self { i2c, address };
+        Self::init(I2cInterface { i2c, address }, Self::init_config())
+    }
 
-        // Verify that the device has the correct ID before continuing. If the ID does
-        // not match either of the expected values then it is likely the wrong chip is
-        // connected.
-        if !Self::DEVICE_IDS.contains(&me.device_id()?) {
-            return Err(Error::SensorError(SensorError::BadChip));
-        }
+    /// Instantiate a new instance of the driver and initialize the device
+    /// with the provided configuration
+    ///
+    /// Every field of `config` is written, in the order described by
+    /// [`Icm42670::apply_config`], so the sensors are only enabled once they
+    /// have been fully configured. An unsupported combination of settings is
+    /// rejected with [`SensorError::InvalidConfig`] before any is written.
+    pub fn new_with_config(i2c: I2C, address: Address, config: Config) -> Result<Self, Error<E>> {
+        Self::init(I2cInterface { i2c, address }, config)
+    }
 
-        // Make sure that any configuration has been restored to the default values when
-        // initializing the driver.
-        me.set_accel_range(AccelRange::default())?;
-        me.set_gyro_range(GyroRange::default())?;
+    /// Instantiate a new instance of the driver for a device which has
+    /// already been configured, without communicating with it
+    ///
+    /// `config` is trusted to be the configuration most recently written to
+    /// the device, and is used for scaling and by methods such as
+    /// [`Icm42670::apply_config_diff`]. This allows the driver to be
+    /// recreated after the MCU wakes from a deep sleep during which the
+    /// device remained powered, without spending any time on the bus.
+    pub fn from_parts(i2c: I2C, address: Address, config: Config) -> Self {
+        Self::from_interface(I2cInterface { i2c, address }, config)
+    }
 
-        // The IMU uses `PowerMode::Sleep` by default, which disables both the accel and
-        // gyro, so we enable them both during driver initialization.
-        me.set_power_mode(PowerMode::SixAxisLowNoise)?;
+    /// Instantiate a new instance of the driver and initialize the device,
+    /// then read back the configured sensor ranges
+    ///
+    /// The cached ranges, which are used for scaling, are populated from the
+    /// values reported by the device rather than those which were written.
+    /// This serves as a sanity check of the bus beyond the device ID:
+    /// [`SensorError::InvalidDiscriminant`] is returned if either register
+    /// holds an impossible value, which indicates a bad read.
+    pub fn new_checked(i2c: I2C, address: Address) -> Result<Self, Error<E>> {
+        let mut me = Self::new(i2c, address)?;
+
+        me.config.accel_range = me.accel_range()?;
+        me.config.gyro_range = me.gyro_range()?;
 
         Ok(me)
     }
@@ -104,14 +222,12 @@ where
         packet_type: FifoPacketType,
         delay: &mut dyn DelayUs<u8>,
     ) -> Result<Self, Error<E>> {
-        let mut me = Self { i2c, address };
+        let mut me = Self::from_parts(i2c, address, Config::default());
 
         // Verify that the device has the correct ID before continuing. If the ID does
         // not match either of the expected values then it is likely the wrong chip is
         // connected.
-        if !Self::DEVICE_IDS.contains(&me.device_id()?) {
-            return Err(Error::SensorError(SensorError::BadChip));
-        }
+        me.check_device_id()?;
 
         // Make sure that any configuration has been restored to the default values when
         // initializing the driver.
@@ -122,29 +238,14 @@ where
         me.set_power_mode(PowerMode::Idle)?;
 
         // setup FIFO configurations
-        me.write_reg(&Bank0::FIFO_CONFIG1, 0)?;
-
         me.write_reg(&Bank0::INT_SOURCE3, 8)?;
-
-        match packet_type {
-            FifoPacketType::Packet1 => {
-                me.write_mreg(delay, RegisterBank::MReg1, &Mreg1::FIFO_CONFIG5, 0x01)?;
-                // no FSYNC
-            }
-            FifoPacketType::Packet2 => {
-                me.write_mreg(delay, RegisterBank::MReg1, &Mreg1::FIFO_CONFIG5, 0x02)?;
-                // no FSYNC
-            }
-            FifoPacketType::Packet3 => {
-                me.write_mreg(delay, RegisterBank::MReg1, &Mreg1::FIFO_CONFIG5, 0x03)?;
-                // no FSYNC
-            }
-            FifoPacketType::Packet4 => {
-                me.write_mreg(delay, RegisterBank::MReg1, &Mreg1::FIFO_CONFIG5, 0x0B)?;
-                //3+8=B
-                // no FSYNC
-            }
-        }
+        me.enable_fifo(
+            FifoConfig {
+                packet: packet_type,
+                mode: FifoMode::Stream,
+            },
+            delay,
+        )?;
         me.write_mreg(delay, RegisterBank::MReg1, &Mreg1::TMST_CONFIG1, 0x15)?; // delta T for ts
 
         // reduce number of generated packtets to 50Hz
@@ -153,7 +254,7 @@ where
 
         // The IMU uses `PowerMode::Sleep` by default, which disables both the accel and
         // gyro, so we enable them both during driver initialization.
-        me.set_power_mode(PowerMode::SixAxisLowNoise)?;
+        me.set_power_mode_staged(PowerMode::SixAxisLowNoise, delay)?;
         for _i in 0..200 {
             delay.delay_us(250);
         }
@@ -165,45 +266,721 @@ where
 
     /// Return the raw interface to the underlying `I2C` instance
     pub fn free(self) -> I2C {
-        self.i2c
+        self.iface.i2c
+    }
+
+    /// Change the I²C address used for all subsequent transactions
+    ///
+    /// This is for designs which drive the `AP_AD0` pin after power-up. The
+    /// caller is responsible for ensuring that the pin matches the new address
+    /// before the next transaction; the driver does not verify it.
+    pub fn set_address(&mut self, address: Address) {
+        self.iface.address = address;
+    }
+}
+
+impl<SPI, CS, S, P> Icm42670<SpiInterface<SPI, CS>>
+where
+    SPI: spi::Transfer<u8, Error = S> + spi::Write<u8, Error = S>,
+    CS: OutputPin<Error = P>,
+    S: Debug,
+    P: Debug,
+{
+    /// Instantiate a new instance of the driver using the 4-wire SPI
+    /// interface and initialize the device
+    ///
+    /// `cs` is the active-low chip select pin, which should be high when this
+    /// is called. See [`SpiInterface`] for the supported bus modes.
+    pub fn new_spi(spi: SPI, cs: CS) -> Result<Self, Error<SpiError<S, P>>> {
+        Self::init(SpiInterface { spi, cs }, Self::init_config())
+    }
+
+    /// Instantiate a new instance of the driver using the 4-wire SPI
+    /// interface and initialize the device with the provided configuration
+    ///
+    /// See [`Icm42670::new_with_config`].
+    pub fn new_spi_with_config(
+        spi: SPI,
+        cs: CS,
+        config: Config,
+    ) -> Result<Self, Error<SpiError<S, P>>> {
+        Self::init(SpiInterface { spi, cs }, config)
+    }
+
+    /// Instantiate a new instance of the driver using the 4-wire SPI
+    /// interface, for a device which has already been configured, without
+    /// communicating with it
+    ///
+    /// See [`Icm42670::from_parts`].
+    pub fn from_parts_spi(spi: SPI, cs: CS, config: Config) -> Self {
+        Self::from_interface(SpiInterface { spi, cs }, config)
+    }
+
+    /// Return the underlying `SPI` instance and chip select pin
+    pub fn free(self) -> (SPI, CS) {
+        (self.iface.spi, self.iface.cs)
+    }
+}
+
+impl<SPI, CS, S, P> Icm42670<Spi3WireInterface<SPI, CS>>
+where
+    SPI: HalfDuplexSpi<Error = S>,
+    CS: OutputPin<Error = P>,
+    S: Debug,
+    P: Debug,
+{
+    /// Instantiate a new instance of the driver using the 3-wire SPI
+    /// interface and initialize the device
+    ///
+    /// `cs` is the active-low chip select pin, which should be high when this
+    /// is called. See [`Spi3WireInterface`] for how the device is switched to
+    /// 3-wire mode.
+    pub fn new_spi_3wire(spi: SPI, cs: CS) -> Result<Self, Error<SpiError<S, P>>> {
+        Self::init(Spi3WireInterface { spi, cs }, Self::init_config())
+    }
+
+    /// Instantiate a new instance of the driver using the 3-wire SPI
+    /// interface, for a device which has already been configured, and
+    /// switched to 3-wire mode, without communicating with it
+    ///
+    /// See [`Icm42670::from_parts`].
+    pub fn from_parts_spi_3wire(spi: SPI, cs: CS, config: Config) -> Self {
+        Self::from_interface(Spi3WireInterface { spi, cs }, config)
+    }
+
+    /// Return the underlying `SPI` instance and chip select pin
+    pub fn free(self) -> (SPI, CS) {
+        (self.iface.spi, self.iface.cs)
+    }
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Unique device identifiers for the ICM-42607 and ICM-42670
+    ///
+    /// The ICM-42607 is the mass-production version of the ICM-42670, and
+    /// differs only by part number and device ID.
+    pub const DEVICE_IDS: [u8; 2] = [
+        0x60, // ICM-42607
+        0x67, // ICM-42670
+    ];
+
+    /// Initialize the device over the provided interface, then apply
+    /// `config`
+    fn init(iface: DI, config: Config) -> Result<Self, Error<E>> {
+        let mut me = Self::from_interface(iface, Config::default());
+        me.select_interface_mode()?;
+
+        // Verify that the device has the correct ID before continuing. If the ID does
+        // not match either of the expected values then it is likely the wrong chip is
+        // connected.
+        me.check_device_id()?;
+
+        // Every field is written, so any configuration is restored to the provided
+        // values when initializing the driver.
+        me.apply_config(config)?;
+
+        Ok(me)
+    }
+
+    /// Default configuration applied when initializing the driver
+    ///
+    /// The IMU uses `PowerMode::Sleep` by default, which disables both the
+    /// accel and gyro, so we enable them both during driver initialization.
+    fn init_config() -> Config {
+        Config {
+            power_mode: PowerMode::SixAxisLowNoise,
+            ..Config::default()
+        }
+    }
+
+    /// Instantiate a new instance of the driver for a device which has
+    /// already been configured, without communicating with it, over the
+    /// provided interface
+    ///
+    /// `config` is trusted to be the configuration most recently written to
+    /// the device, and is used for scaling and by methods such as
+    /// [`Icm42670::apply_config_diff`]. This allows the driver to be
+    /// recreated after the MCU wakes from a deep sleep during which the
+    /// device remained powered, without spending any time on the bus.
+    ///
+    /// This is the equivalent of [`Icm42670::from_parts`] for any
+    /// [`Interface`].
+    pub fn from_interface(iface: DI, config: Config) -> Self {
+        Self {
+            iface,
+            config,
+            sleep_config: None,
+            gyro_deadband: 0.0,
+            software_bias: Bias::default(),
+            gyro_temp_coeff: None,
+            last_accel_raw: None,
+            accel_repeated: false,
+            accel_repeats: 0,
+            last_gyro_raw: None,
+            gyro_repeats: 0,
+            stuck_threshold: None,
+            dmp_initialized: false,
+            verify_writes: false,
+            fifo_overflow: false,
+            accel_ema: Ema::disabled(),
+            gyro_ema: Ema::disabled(),
+            fifo_packet: None,
+            fifo_watermark_packets: None,
+            pending_events: IntStatus::default(),
+            max_transaction_len: None,
+            bus_retries: 0,
+            step_count_taken: 0,
+            freefall: None,
+            byte_order: ByteOrder::BigEndian,
+            accel_avg: AccelAvg::default(),
+        }
+    }
+
+    /// Destroy the driver instance, returning its bus interface
+    ///
+    /// This is the equivalent of `free` for any [`Interface`].
+    pub fn free_interface(self) -> DI {
+        self.iface
+    }
+
+    /// Write every field of the provided configuration to the device
+    ///
+    /// The configuration is first checked with [`Config::validate`], so that
+    /// the device is never left partially configured. The sensor
+    /// configuration registers are contiguous, so they are read and written
+    /// back in a single transaction each. The power mode is written last, so
+    /// that the sensors are only enabled once they have been fully
+    /// configured.
+    pub fn apply_config(&mut self, config: Config) -> Result<(), Error<E>> {
+        config.validate()?;
+
+        // GYRO_CONFIG0, ACCEL_CONFIG0, TEMP_CONFIG0, GYRO_CONFIG1, ACCEL_CONFIG1
+        let mut regs = [0u8; 5];
+        self.read_regs(&Bank0::GYRO_CONFIG0, &mut regs)?;
+
+        regs[0] = apply_bitfield(regs[0], config.gyro_range);
+        regs[0] = apply_bitfield(regs[0], config.gyro_odr);
+        regs[1] = apply_bitfield(regs[1], config.accel_range);
+        regs[1] = apply_bitfield(regs[1], config.accel_odr);
+        regs[3] = apply_bitfield(regs[3], config.gyro_bw);
+        regs[4] = apply_bitfield(regs[4], config.accel_bw);
+
+        self.write_regs(&Bank0::GYRO_CONFIG0, &regs)?;
+
+        if self.verify_writes {
+            self.verify_regs(&Bank0::GYRO_CONFIG0, &regs)?;
+        }
+
+        self.config = Config {
+            power_mode: self.config.power_mode,
+            ..config
+        };
+
+        self.set_power_mode(config.power_mode)
+    }
+
+    /// Write only the fields of the provided configuration which differ from
+    /// the configuration most recently written to the device
+    ///
+    /// This minimizes bus traffic when retuning a running device, and the
+    /// time during which it is partially reconfigured. The configuration is
+    /// first checked with [`Config::validate`]. The comparison is made
+    /// against the driver's cached configuration, so any change made to the
+    /// device by other means, such as by [`Icm42670::soft_reset`], is not
+    /// detected; use [`Icm42670::apply_config`] to write every field.
+    pub fn apply_config_diff(&mut self, config: Config) -> Result<(), Error<E>> {
+        config.validate()?;
+
+        let current = self.config;
+
+        if config.gyro_range != current.gyro_range || config.gyro_odr != current.gyro_odr {
+            self.update_fields(&Bank0::GYRO_CONFIG0, config.gyro_range, config.gyro_odr)?;
+            self.config.gyro_range = config.gyro_range;
+            self.config.gyro_odr = config.gyro_odr;
+        }
+        if config.accel_range != current.accel_range || config.accel_odr != current.accel_odr {
+            self.update_fields(&Bank0::ACCEL_CONFIG0, config.accel_range, config.accel_odr)?;
+            self.config.accel_range = config.accel_range;
+            self.config.accel_odr = config.accel_odr;
+        }
+        if config.gyro_bw != current.gyro_bw {
+            self.set_gyro_bw(config.gyro_bw)?;
+        }
+        if config.accel_bw != current.accel_bw {
+            self.set_accel_bw(config.accel_bw)?;
+        }
+        if config.power_mode != current.power_mode {
+            self.set_power_mode(config.power_mode)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write every field of the provided configuration to the device, then
+    /// read it back, restoring the previous configuration if it does not
+    /// match
+    ///
+    /// The power mode is changed last, using
+    /// [`Icm42670::set_power_mode_staged`]. If any register does not read back
+    /// as written, or any transfer fails, the registers which held the
+    /// previous configuration are rewritten and read back. A register which
+    /// did not read back as written is reported as
+    /// [`SensorError::ConfigRollback`], and any other error is returned
+    /// unchanged once the previous configuration has been restored. If the
+    /// rollback itself fails, or does not read back as written, its error is
+    /// returned instead, and the device may be left partially reconfigured.
+    pub fn apply_config_checked(
+        &mut self,
+        config: Config,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        config.validate()?;
+
+        let previous = self.config;
+        let mut saved = [0u8; 5];
+        self.read_regs(&Bank0::GYRO_CONFIG0, &mut saved)?;
+        let saved_power = self.read_reg(&Bank0::PWR_MGMT0)?;
+
+        let mut regs = saved;
+        regs[0] = apply_bitfield(regs[0], config.gyro_range);
+        regs[0] = apply_bitfield(regs[0], config.gyro_odr);
+        regs[1] = apply_bitfield(regs[1], config.accel_range);
+        regs[1] = apply_bitfield(regs[1], config.accel_odr);
+        regs[3] = apply_bitfield(regs[3], config.gyro_bw);
+        regs[4] = apply_bitfield(regs[4], config.accel_bw);
+
+        match self.write_config_verified(&regs, config.power_mode, delay) {
+            Ok(()) => {
+                self.config = config;
+
+                Ok(())
+            }
+            Err(err) => {
+                self.write_regs(&Bank0::GYRO_CONFIG0, &saved)?;
+                self.verify_regs(&Bank0::GYRO_CONFIG0, &saved)?;
+                self.write_reg(&Bank0::PWR_MGMT0, saved_power)?;
+                self.verify_regs(&Bank0::PWR_MGMT0, &[saved_power])?;
+                self.config = previous;
+
+                match err {
+                    Error::SensorError(SensorError::WriteVerifyFailed { reg, expected, got }) => {
+                        Err(Error::SensorError(SensorError::ConfigRollback {
+                            reg,
+                            expected,
+                            got,
+                        }))
+                    }
+                    err => Err(err),
+                }
+            }
+        }
+    }
+
+    /// Write the accelerometer's settings, leaving the gyroscope's unchanged
+    ///
+    /// The resulting configuration is checked with [`Config::validate`], and
+    /// only the fields which changed are written; see
+    /// [`Icm42670::apply_config_diff`].
+    pub fn set_accel_config(&mut self, accel: AccelConfig) -> Result<(), Error<E>> {
+        self.apply_config_diff(self.config.with_accel(accel))
+    }
+
+    /// Write the gyroscope's settings, leaving the accelerometer's unchanged
+    ///
+    /// See [`Icm42670::set_accel_config`].
+    pub fn set_gyro_config(&mut self, gyro: GyroConfig) -> Result<(), Error<E>> {
+        self.apply_config_diff(self.config.with_gyro(gyro))
+    }
+
+    /// Put the device into its lowest-power state
+    ///
+    /// Both sensors are turned off, which also stops the temperature sensor.
+    /// When `keep_oscillator` is set the RC oscillator is left running
+    /// ([`PowerMode::Idle`]), which allows registers in the MREG banks to be
+    /// accessed while asleep at the cost of a slightly higher current draw.
+    ///
+    /// The current configuration is remembered and restored by
+    /// [`Icm42670::wake`].
+    pub fn sleep(&mut self, keep_oscillator: bool) -> Result<(), Error<E>> {
+        let mode = if keep_oscillator {
+            PowerMode::Idle
+        } else {
+            PowerMode::Sleep
+        };
+
+        // Calling this function while already asleep must not overwrite the
+        // configuration which is to be restored on wake.
+        let config = self.sleep_config.unwrap_or(self.config);
+        self.set_power_mode(mode)?;
+        self.sleep_config = Some(config);
+
+        Ok(())
+    }
+
+    /// Wake the device from sleep, restoring the configuration which was
+    /// active when [`Icm42670::sleep`] was called
+    pub fn wake(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        let config = self.sleep_config.unwrap_or(self.config);
+        config.validate()?;
+
+        self.apply_config(Config {
+            power_mode: self.config.power_mode,
+            ..config
+        })?;
+        self.set_power_mode_staged(config.power_mode, delay)?;
+        self.sleep_config = None;
+
+        Ok(())
+    }
+
+    /// Enable or disable verification of configuration writes
+    ///
+    /// When enabled, every configuration register is read back after being
+    /// written and [`SensorError::WriteVerifyFailed`] is returned if its
+    /// value does not match. This doubles the bus traffic of the setters, so
+    /// it is disabled by default.
+    pub fn set_verify_writes(&mut self, enable: bool) {
+        self.verify_writes = enable;
+    }
+
+    /// Limit the number of bytes transferred in a single bus transaction
+    ///
+    /// Some HALs cannot transfer more than a fixed number of bytes at once.
+    /// With a limit set, longer burst reads and writes, such as draining the
+    /// FIFO with [`Icm42670::read_fifo`], are transparently split into
+    /// several transactions. The limit for a write includes the register
+    /// address byte, and is raised to the minimum of two bytes if necessary.
+    /// `None`, the default, removes the limit.
+    pub fn set_max_transaction_len(&mut self, len: Option<usize>) {
+        self.max_transaction_len = len.map(|len| len.max(2));
+    }
+
+    /// Retry failed bus transactions up to `retries` times
+    ///
+    /// On a noisy bus the device may occasionally fail to acknowledge a
+    /// transaction. With retries set, every register read and write is
+    /// repeated until it succeeds or the retries are exhausted, in which case
+    /// the last bus error is returned. The bus error types of the HALs do not
+    /// distinguish transient from permanent failures, so any error is
+    /// retried. A FIFO read which fails part way through may already have
+    /// consumed some of the FIFO's contents, which the retry then skips. The
+    /// default of 0 returns every bus error immediately.
+    pub fn set_bus_retries(&mut self, retries: u8) {
+        self.bus_retries = retries;
+    }
+
+    /// Report data which stops changing as an error
+    ///
+    /// A sensor which has frozen, or a bus which is returning the same bytes
+    /// for every transaction, otherwise looks like very stable data. With a
+    /// threshold set, [`SensorError::StuckData`] is returned once more than
+    /// `threshold` consecutive raw readings of the accelerometer or gyroscope
+    /// repeat the one before them byte for byte, and for every identical
+    /// reading after that. Choose a threshold large enough that a sensor at
+    /// rest, or one read faster than its output data rate, does not trigger
+    /// it. `None`, the default, disables the check.
+    ///
+    /// Only reads of a single sensor are checked; the burst reads of every
+    /// sensor at once are not.
+    pub fn set_stuck_detection(&mut self, threshold: Option<u16>) {
+        self.stuck_threshold = threshold;
+        self.accel_repeats = 0;
+        self.gyro_repeats = 0;
     }
 
     /// Read the ID of the connected device
+    ///
+    /// This is the value of the `WHO_AM_I` register, which should be one of
+    /// [`Icm42670::DEVICE_IDS`].
     pub fn device_id(&mut self) -> Result<u8, Error<E>> {
         self.read_reg(&Bank0::WHO_AM_I)
     }
 
+    /// Return [`SensorError::BadChip`] if the connected device does not
+    /// report one of the expected IDs.
+    fn check_device_id(&mut self) -> Result<(), Error<E>> {
+        let found = self.device_id()?;
+
+        if Self::DEVICE_IDS.contains(&found) {
+            Ok(())
+        } else {
+            Err(Error::SensorError(SensorError::BadChip { found }))
+        }
+    }
+
+    /// Is the internal clock running?
+    ///
+    /// Registers in the MREG1, MREG2, and MREG3 banks can only be accessed
+    /// while the internal clock is running.
+    pub fn mclk_ready(&mut self) -> Result<bool, Error<E>> {
+        // `MCLK_RDY` occupies bit 3 in the register
+        let ready = self.read_reg(&Bank0::MCLK_RDY)? & 0b0000_1000 != 0;
+
+        Ok(ready)
+    }
+
+    /// Has the device finished initializing since the last power-on or
+    /// software reset?
+    ///
+    /// The flag is cleared by reading `INT_STATUS`, including by this method,
+    /// so it only reports `true` once per reset.
+    pub fn reset_done(&mut self) -> Result<bool, Error<E>> {
+        // `RESET_DONE_INT` occupies bit 4 in the register
+        let done = self.read_status_reg(Bank0::INT_STATUS)? & 0b0001_0000 != 0;
+
+        Ok(done)
+    }
+
+    /// Is new sensor data available since the status was last read?
+    ///
+    /// The flag is cleared by reading `INT_STATUS_DRDY`, including by this
+    /// method and by the methods which wait for new data, such as
+    /// [`Icm42670::accel_raw_when_ready`], so each new sample is only
+    /// reported once. Do not poll this while also waiting with one of those
+    /// methods, or the two will consume each other's notifications.
+    pub fn data_ready(&mut self) -> Result<bool, Error<E>> {
+        // `DATA_RDY_INT` occupies bit 0 in the register
+        let ready = self.read_status_reg(Bank0::INT_STATUS_DRDY)? & 0b0000_0001 != 0;
+
+        Ok(ready)
+    }
+
     /// Perform a software-reset on the device
+    ///
+    /// The device does not respond for 1ms afterwards; completion is reported
+    /// by [`Icm42670::reset_done`], or use [`Icm42670::soft_reset_blocking`]
+    /// to wait for it. Every register returns to its reset value, so the
+    /// driver's cached configuration and FIFO format are returned to their
+    /// defaults to match. Settings held only by the driver, such as the
+    /// smoothing filters and the gyro deadband, are kept.
     pub fn soft_reset(&mut self) -> Result<(), Error<E>> {
-        self.update_reg(&Bank0::SIGNAL_PATH_RESET, 0x10, 0b0001_0000)
+        self.update_reg(&Bank0::SIGNAL_PATH_RESET, 0x10, 0b0001_0000)?;
+
+        self.config = Config::default();
+        self.sleep_config = None;
+        self.last_accel_raw = None;
+        self.accel_repeated = false;
+        self.accel_repeats = 0;
+        self.last_gyro_raw = None;
+        self.gyro_repeats = 0;
+        self.dmp_initialized = false;
+        self.fifo_overflow = false;
+        self.fifo_packet = None;
+        self.fifo_watermark_packets = None;
+        self.pending_events = IntStatus::default();
+        self.step_count_taken = 0;
+        self.byte_order = ByteOrder::BigEndian;
+        self.accel_avg = AccelAvg::default();
+
+        Ok(())
+    }
+
+    /// Perform a software-reset on the device, and wait for it to complete
+    ///
+    /// [`SensorError::Timeout`] is returned if the device has not reported
+    /// completion within 5ms.
+    pub fn soft_reset_blocking(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        self.soft_reset()?;
+        self.wait_reset_done(delay)
     }
 
     /// Return the normalized gyro data for each of the three axes
+    ///
+    /// The data is scaled using the range most recently configured through
+    /// the driver, which is cached so that no additional register read is
+    /// needed; the accelerometer data is scaled in the same way. Until a
+    /// range is configured, the default ranges are assumed. The data is
+    /// smoothed if enabled with [`Icm42670::set_gyro_ema`]. Any
+    /// axis whose magnitude then falls below the configured deadband is
+    /// reported as zero; see [`Icm42670::set_gyro_deadband`].
     pub fn gyro_norm(&mut self) -> Result<F32x3, Error<E>> {
-        let range = self.gyro_range()?;
-        let scale = range.scale_factor();
+        let (filtered, _) = self.read_gyro_filtered()?;
 
-        // Scale the raw Gyroscope data using the appropriate factor based on the
-        // configured range.
-        let raw = self.gyro_raw()?;
-        let x = raw.x as f32 / scale;
-        let y = raw.y as f32 / scale;
-        let z = raw.z as f32 / scale;
+        Ok(filtered)
+    }
+
+    /// Return the magnitude of the normalized gyro data
+    ///
+    /// This is the total rate of rotation, about whichever axis, with the
+    /// same smoothing and deadband as [`Icm42670::gyro_norm`].
+    pub fn read_gyro_magnitude(&mut self) -> Result<AngularVelocity, Error<E>> {
+        let gyro = self.gyro_norm()?;
+
+        Ok(AngularVelocity(math::norm(gyro.x, gyro.y, gyro.z)))
+    }
+
+    /// Return the normalized gyro data for each of the three axes, in rad/sec
+    ///
+    /// This is [`Icm42670::gyro_norm`] converted from deg/sec, so the same
+    /// smoothing and deadband apply.
+    pub fn gyro_rads(&mut self) -> Result<F32x3, Error<E>> {
+        let dps = self.gyro_norm()?;
+
+        Ok(F32x3::new(
+            dps.x.to_radians(),
+            dps.y.to_radians(),
+            dps.z.to_radians(),
+        ))
+    }
+
+    /// Return the magnitude of the normalized accelerometer data
+    ///
+    /// A device at rest measures only gravity, so reads close to 1g. The data
+    /// is smoothed if enabled with [`Icm42670::set_accel_ema`]. The square root
+    /// is provided by `micromath`, or by `libm` if the `libm` feature is
+    /// enabled.
+    pub fn read_accel_magnitude(&mut self) -> Result<Acceleration, Error<E>> {
+        let accel = self.read_accel_norm()?;
+
+        Ok(Acceleration(math::norm(accel.x, accel.y, accel.z)))
+    }
+
+    /// Return the normalized accelerometer data if a new sample is available,
+    /// or `None` if it has not changed since the status was last read
+    ///
+    /// This lets a loop polling faster than the output data rate tell fresh
+    /// samples from repeated ones. Availability is checked with
+    /// [`Icm42670::data_ready`], whose caveats apply; in particular, when the
+    /// gyroscope is also running, a new gyro sample alone is reported as new
+    /// data.
+    pub fn read_accel_checked(&mut self) -> Result<Option<F32x3>, Error<E>> {
+        if !self.data_ready()? {
+            return Ok(None);
+        }
+
+        self.read_accel_norm().map(Some)
+    }
 
-        Ok(F32x3::new(x, y, z))
+    /// Smooth subsequent normalized accelerometer readings using an
+    /// exponential moving average
+    ///
+    /// Each reading is weighted by `alpha`, and the previous output by
+    /// `1.0 - alpha`, so smaller values give smoother but slower-responding
+    /// output. An `alpha` of `1.0`, the default, disables the filter. Changing
+    /// `alpha` restarts the filter from the next reading. The raw data is
+    /// never affected.
+    pub fn set_accel_ema(&mut self, alpha: f32) {
+        self.accel_ema.set_alpha(alpha);
+    }
+
+    /// Smooth subsequent normalized gyro readings using an exponential moving
+    /// average
+    ///
+    /// See [`Icm42670::set_accel_ema`] for the meaning of `alpha`.
+    pub fn set_gyro_ema(&mut self, alpha: f32) {
+        self.gyro_ema.set_alpha(alpha);
+    }
+
+    /// Set the deadband, in deg/sec, below which normalized gyro readings are
+    /// reported as zero
+    ///
+    /// This suppresses the slow drift caused by bias noise when the gyro is
+    /// used as a rotation input. A deadband of `0.0`, the default, disables
+    /// it. The raw gyro data is never affected.
+    pub fn set_gyro_deadband(&mut self, dps: f32) {
+        self.gyro_deadband = dps;
     }
 
     /// Read the raw gyro data for each of the three axes
+    ///
+    /// [`SensorError::SensorNotActive`] is returned if the gyroscope is not
+    /// producing data in the current power mode, including
+    /// [`PowerMode::Standby`]. The same applies to every method which reads
+    /// the gyro data, and likewise to those reading the accelerometer data.
     pub fn gyro_raw(&mut self) -> Result<I16x3, Error<E>> {
-        let x = self.read_reg_i16(&Bank0::GYRO_DATA_X1, &Bank0::GYRO_DATA_X0)?;
-        let y = self.read_reg_i16(&Bank0::GYRO_DATA_Y1, &Bank0::GYRO_DATA_Y0)?;
-        let z = self.read_reg_i16(&Bank0::GYRO_DATA_Z1, &Bank0::GYRO_DATA_Z0)?;
+        self.require_gyro()?;
+
+        let raw = self.read_vector(&Bank0::GYRO_DATA_X1)?;
+
+        let repeated = self.last_gyro_raw == Some(raw);
+        self.last_gyro_raw = Some(raw);
+        self.gyro_repeats = count_repeat(self.gyro_repeats, repeated);
+        self.check_stuck(self.gyro_repeats, "gyroscope")?;
+
+        Ok(raw)
+    }
+
+    /// Wait for new sensor data, then read the raw accelerometer data for
+    /// each of the three axes
+    ///
+    /// If no new data becomes available within `timeout_ms` milliseconds
+    /// then [`SensorError::Timeout`] is returned rather than stale data. If
+    /// the accelerometer is not enabled then
+    /// [`SensorError::SensorNotActive`] is returned without waiting.
+    pub fn accel_raw_when_ready(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+        timeout_ms: u16,
+    ) -> Result<I16x3, Error<E>> {
+        self.require_accel()?;
+        self.wait_data_ready_for(delay, timeout_ms as u32 * 1000)?;
+
+        self.read_accel_raw()
+    }
+
+    /// Wait for new sensor data, then read the raw gyro data for each of the
+    /// three axes
+    ///
+    /// See [`Icm42670::accel_raw_when_ready`].
+    pub fn gyro_raw_when_ready(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+        timeout_ms: u16,
+    ) -> Result<I16x3, Error<E>> {
+        self.require_gyro()?;
+        self.wait_data_ready_for(delay, timeout_ms as u32 * 1000)?;
+
+        self.gyro_raw()
+    }
+
+    /// Read the raw accelerometer and gyroscope data in a single transaction,
+    /// writing each value to `out` in the requested byte order
+    ///
+    /// The values are written in the order accelerometer X, Y, Z followed by
+    /// gyroscope X, Y, Z, two bytes each.
+    pub fn read_imu_raw_bytes(
+        &mut self,
+        order: ByteOrder,
+        out: &mut [u8; 12],
+    ) -> Result<(), Error<E>> {
+        self.require_accel()?;
+        self.require_gyro()?;
 
-        Ok(I16x3::new(x, y, z))
+        self.read_regs(&Bank0::ACCEL_DATA_X1, out)?;
+
+        if order != self.byte_order {
+            for value in out.chunks_exact_mut(2) {
+                value.swap(0, 1);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read every sensor in a single transaction and return the scaled
+    /// values as `[ax, ay, az, gx, gy, gz, temp]`
+    ///
+    /// The order is fixed, matching [`Sample::to_array`], so that the result
+    /// can be used directly as the input of an inference model. The data is
+    /// scaled using the cached ranges, and neither smoothing nor the gyro
+    /// deadband is applied.
+    pub fn read_feature_vector(&mut self) -> Result<[f32; 7], Error<E>> {
+        let sample = self.read_sample_burst()?;
+
+        Ok(sample.to_array())
     }
 
     /// Read the built-in temperature sensor and return the value in degrees
     /// centigrade
+    ///
+    /// The temperature sensor is only sampled alongside the other sensors, so
+    /// in [`PowerMode::AccelLowPower`] it updates at the duty-cycled rate and
+    /// this returns the most recently latched value, which may be stale. Use
+    /// [`Icm42670::temperature_fresh`] to wait for a new sample instead.
     pub fn temperature(&mut self) -> Result<f32, Error<E>> {
         let raw = self.temperature_raw()? as f32;
         let deg = (raw / 128.0) + 25.0;
@@ -211,116 +988,626 @@ where
         Ok(deg)
     }
 
+    /// Read the built-in temperature sensor and return the value in degrees
+    /// Fahrenheit
+    pub fn temperature_f(&mut self) -> Result<f32, Error<E>> {
+        let deg = self.temperature()? * 9.0 / 5.0 + 32.0;
+
+        Ok(deg)
+    }
+
+    /// Read the built-in temperature sensor and return the value in kelvin
+    pub fn temperature_k(&mut self) -> Result<f32, Error<E>> {
+        let kelvin = self.temperature()? + 273.15;
+
+        Ok(kelvin)
+    }
+
+    /// Wait for a new temperature sample, then return it in degrees
+    /// centigrade
+    ///
+    /// In [`PowerMode::AccelLowPower`] this blocks until the next
+    /// duty-cycled sample is available, so that the reading is never older
+    /// than the accompanying accelerometer data. In every other power mode
+    /// the temperature is sampled continuously, so it is read immediately.
+    pub fn temperature_fresh(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<f32, Error<E>> {
+        if self.config.power_mode == PowerMode::AccelLowPower {
+            self.wait_data_ready(delay)?;
+        }
+
+        self.temperature()
+    }
+
     /// Read the raw data from the built-in temperature sensor
+    ///
+    /// The temperature sensor only runs while the accelerometer or gyroscope
+    /// is producing data, so in [`PowerMode::Sleep`], [`PowerMode::Idle`], and
+    /// [`PowerMode::Standby`] [`SensorError::SensorNotActive`] is returned
+    /// rather than invalid data. The same applies to every method which
+    /// reads the temperature.
     pub fn temperature_raw(&mut self) -> Result<i16, Error<E>> {
+        self.require_temperature()?;
+
         self.read_reg_i16(&Bank0::TEMP_DATA1, &Bank0::TEMP_DATA0)
     }
 
+    /// Read the accelerometer, gyroscope, and temperature sensor and return
+    /// the scaled values
+    pub fn read_sample(&mut self) -> Result<Sample, Error<E>> {
+        let accel = self.read_accel_norm()?;
+        let gyro = self.gyro_norm()?;
+        let temp = self.temperature()?;
+
+        Ok(Sample { accel, gyro, temp })
+    }
+
+    /// Read the accelerometer, gyroscope, and temperature sensor in a single
+    /// bus transaction and return the scaled values
+    ///
+    /// Unlike [`Icm42670::read_sample`], which reads each sensor separately,
+    /// every value is guaranteed to come from the same sample, and the bus
+    /// overhead is much lower. Both sensors must be producing data. The
+    /// values are scaled using the cached ranges, and the software offsets
+    /// are subtracted; no smoothing or deadband is applied.
+    pub fn read_all(&mut self) -> Result<Sample, Error<E>> {
+        self.read_sample_burst()
+    }
+
+    /// Read every sensor in a single bus transaction, as
+    /// [`Icm42670::read_all`] does, and return the values as plain arrays
+    ///
+    /// The data registers carry no timestamp, so [`Sample6Dof::timestamp`]
+    /// is always `None`; read the FIFO with timestamps enabled and convert its
+    /// [`FifoRecord`]s for timestamped samples.
+    pub fn read_6dof(&mut self) -> Result<Sample6Dof, Error<E>> {
+        Ok(self.read_sample_burst()?.into())
+    }
+
+    /// Read `N` consecutive samples of every sensor, as
+    /// [`Icm42670::read_all`] does
+    ///
+    /// A new sample is awaited before each read, so no sample is returned
+    /// twice, and [`SensorError::Timeout`] is returned if one does not arrive
+    /// within two output data rate periods. The samples are returned on the
+    /// stack, so keep `N` small; for longer captures, use the FIFO.
+    pub fn read_samples<const N: usize>(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<[Sample; N], Error<E>> {
+        let mut samples = [Sample::default(); N];
+
+        for sample in samples.iter_mut() {
+            self.wait_data_ready(delay)?;
+            *sample = self.read_sample_burst()?;
+        }
+
+        Ok(samples)
+    }
+
+    /// Read every sensor, as [`Icm42670::read_all`] does, if a new sample is
+    /// available, otherwise return `None` immediately
+    ///
+    /// This never waits, so it can be called from the handler of the
+    /// interrupt pin to which the data ready condition is routed with
+    /// [`Icm42670::route_data_ready`]. Checking the status with
+    /// [`Icm42670::data_ready`] clears the condition, de-asserting a latched
+    /// pin, unless [`Icm42670::set_interrupt_clear_mode`] has selected
+    /// otherwise, in which case reading the data clears it instead.
+    pub fn poll(&mut self) -> Result<Option<Sample>, Error<E>> {
+        if !self.data_ready()? {
+            return Ok(None);
+        }
+
+        self.read_sample_burst().map(Some)
+    }
+
+    /// Read the accelerometer, gyroscope, and temperature sensor and return
+    /// the scaled values both with and without smoothing applied, in that
+    /// order
+    ///
+    /// See [`Icm42670::set_accel_ema`] and [`Icm42670::set_gyro_ema`]. The
+    /// filters are advanced exactly as by [`Icm42670::read_sample`].
+    pub fn read_sample_ema(&mut self) -> Result<(Sample, Sample), Error<E>> {
+        let (accel, accel_unfiltered) = self.read_accel_filtered()?;
+        let (gyro, gyro_unfiltered) = self.read_gyro_filtered()?;
+        let temp = self.temperature()?;
+
+        let filtered = Sample { accel, gyro, temp };
+        let unfiltered = Sample {
+            accel: accel_unfiltered,
+            gyro: gyro_unfiltered,
+            temp,
+        };
+
+        Ok((filtered, unfiltered))
+    }
+
+    /// Read the accelerometer and return the tilt of the device relative to
+    /// gravity
+    ///
+    /// The angles are computed from the smoothed accelerometer data, so
+    /// setting a filter with [`Icm42670::set_accel_ema`] gives steadier angles
+    /// for applications such as a digital level. The angles are only
+    /// meaningful while the device is not otherwise accelerating.
+    pub fn read_inclination(&mut self) -> Result<Inclination, Error<E>> {
+        let (accel, _) = self.read_accel_filtered()?;
+
+        Ok(Inclination::from_accel(accel))
+    }
+
+    /// Did the most recent accelerometer read return exactly the same data as
+    /// the read before it?
+    ///
+    /// The accelerometer output only updates at its configured output data
+    /// rate, and in [`PowerMode::AccelLowPower`] it is duty-cycled at that
+    /// rate, so reading faster than the ODR returns repeated samples. Filters
+    /// which should only consume new measurements can use this to skip them.
+    pub fn accel_repeated(&self) -> bool {
+        self.accel_repeated
+    }
+
     /// Return the currently configured power mode
     pub fn power_mode(&mut self) -> Result<PowerMode, Error<E>> {
-        //  `GYRO_MODE` occupies bits 3:2 in the register
+        //      `IDLE` occupies bit 4 in the register
+        // `GYRO_MODE` occupies bits 3:2 in the register
         // `ACCEL_MODE` occupies bits 1:0 in the register
-        let bits = self.read_reg(&Bank0::PWR_MGMT0)? & 0xF;
-        let mode = PowerMode::try_from(bits)?;
-
-        Ok(mode)
+        self.read_field(&Bank0::PWR_MGMT0)
     }
 
     /// Set the power mode of the IMU
+    ///
+    /// This writes the new mode immediately. When turning sensors on, prefer
+    /// [`Icm42670::set_power_mode_staged`], which observes the datasheet's
+    /// timing requirements.
     pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), Error<E>> {
-        self.update_reg(&Bank0::PWR_MGMT0, mode.bits(), PowerMode::BITMASK)
+        self.update_field(&Bank0::PWR_MGMT0, mode)?;
+        self.config.power_mode = mode;
+
+        Ok(())
+    }
+
+    /// Return the currently configured operating mode of the accelerometer
+    pub fn accel_mode(&mut self) -> Result<AccelMode, Error<E>> {
+        // `ACCEL_MODE` occupies bits 1:0 in the register
+        self.read_field(&Bank0::PWR_MGMT0)
+    }
+
+    /// Set the operating mode of the accelerometer, leaving the gyroscope
+    /// unchanged
+    ///
+    /// This allows combinations which no [`PowerMode`] describes, such as a
+    /// duty-cycled accelerometer with the gyroscope in standby. While the
+    /// device is in such a combination [`Icm42670::power_mode`] returns
+    /// [`SensorError::InvalidDiscriminant`], and the driver's record of the
+    /// power mode is not updated.
+    pub fn set_accel_mode(&mut self, mode: AccelMode) -> Result<(), Error<E>> {
+        self.update_field(&Bank0::PWR_MGMT0, mode)?;
+        self.update_power_mode_bits(apply_bitfield(self.config.power_mode as u8, mode));
+
+        Ok(())
+    }
+
+    /// Return the currently configured operating mode of the gyroscope
+    pub fn gyro_mode(&mut self) -> Result<GyroMode, Error<E>> {
+        // `GYRO_MODE` occupies bits 3:2 in the register
+        self.read_field(&Bank0::PWR_MGMT0)
+    }
+
+    /// Set the operating mode of the gyroscope, leaving the accelerometer
+    /// unchanged
+    ///
+    /// See [`Icm42670::set_accel_mode`] regarding combinations which no
+    /// [`PowerMode`] describes.
+    pub fn set_gyro_mode(&mut self, mode: GyroMode) -> Result<(), Error<E>> {
+        self.update_field(&Bank0::PWR_MGMT0, mode)?;
+        self.update_power_mode_bits(apply_bitfield(self.config.power_mode as u8, mode));
+
+        Ok(())
+    }
+
+    /// Turn the gyroscope fully on or off, leaving the accelerometer
+    /// unchanged
+    ///
+    /// This is [`Icm42670::set_gyro_mode`] with [`GyroMode::LowNoise`] or
+    /// [`GyroMode::Off`]. No register writes may be issued for 200µs after
+    /// turning the gyroscope on.
+    pub fn set_gyro_enabled(&mut self, on: bool) -> Result<(), Error<E>> {
+        self.set_gyro_mode(if on {
+            GyroMode::LowNoise
+        } else {
+            GyroMode::Off
+        })
+    }
+
+    /// Duty-cycle the accelerometer while keeping the gyroscope's drive
+    /// circuitry running
+    ///
+    /// This draws more current than [`PowerMode::AccelLowPower`], but the
+    /// gyroscope can then be brought fully online with
+    /// [`Icm42670::promote_to_sixaxis`] much faster than from a cold start,
+    /// for example in response to a wake on motion interrupt. No
+    /// [`PowerMode`] describes this combination, so the driver records it as
+    /// [`PowerMode::AccelLowPower`], which is accurate as far as the data
+    /// produced is concerned.
+    pub fn fast_wake_profile(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        self.set_gyro_mode(GyroMode::Standby)?;
+        delay.delay_us(200);
+        self.set_accel_mode(AccelMode::LowPower)?;
+        delay.delay_us(200);
+        self.config.power_mode = PowerMode::AccelLowPower;
+
+        Ok(())
+    }
+
+    /// Turn both sensors fully on, typically to leave
+    /// [`Icm42670::fast_wake_profile`]
+    ///
+    /// The accelerometer output data rate must be one which is supported in
+    /// [`PowerMode::SixAxisLowNoise`].
+    pub fn promote_to_sixaxis(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        self.set_power_mode_staged(PowerMode::SixAxisLowNoise, delay)
+    }
+
+    /// Set the power mode of the IMU, bringing up the sensors in stages
+    ///
+    /// No register writes may be issued for 200µs after a sensor is turned
+    /// on. When both sensors are turned on together, the accelerometer is
+    /// started first and the gyroscope 200µs later, which avoids a current
+    /// spike and ensures that the first samples are valid. In every case this
+    /// waits the required 200µs after turning a sensor on before returning.
+    pub fn set_power_mode_staged(
+        &mut self,
+        mode: PowerMode,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        let current = self.config.power_mode;
+        let accel_starting = mode.accel_enabled() && !current.accel_enabled();
+        let gyro_starting = mode.gyro_enabled() && !current.gyro_enabled();
+
+        if accel_starting && gyro_starting {
+            self.set_power_mode(PowerMode::AccelLowNoise)?;
+            delay.delay_us(200);
+        }
+
+        self.set_power_mode(mode)?;
+
+        if accel_starting || gyro_starting {
+            delay.delay_us(200);
+        }
+
+        Ok(())
+    }
+
+    /// Set the power mode of the IMU as [`Icm42670::set_power_mode_staged`]
+    /// does, then wait until every sensor turned on is producing valid data
+    ///
+    /// The gyroscope takes up to 45ms to start, and the accelerometer up to
+    /// 10ms, so this waits for the slower of the sensors which were off.
+    /// Nothing further is waited for if no sensor was turned on.
+    pub fn set_power_mode_blocking(
+        &mut self,
+        mode: PowerMode,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        const ACCEL_STARTUP_MS: u16 = 10;
+        const GYRO_STARTUP_MS: u16 = 45;
+
+        let current = self.config.power_mode;
+        self.set_power_mode_staged(mode, delay)?;
+
+        if mode.gyro_enabled() && !current.gyro_enabled() {
+            delay_ms(delay, GYRO_STARTUP_MS);
+        } else if mode.accel_enabled() && !current.accel_enabled() {
+            delay_ms(delay, ACCEL_STARTUP_MS);
+        }
+
+        Ok(())
     }
 
     /// Return the currently configured accelerometer range
     pub fn accel_range(&mut self) -> Result<AccelRange, Error<E>> {
         // `ACCEL_UI_FS_SEL` occupies bits 6:5 in the register
-        let fs_sel = self.read_reg(&Bank0::ACCEL_CONFIG0)? >> 5;
-        let range = AccelRange::try_from(fs_sel)?;
-
-        Ok(range)
+        self.read_field(&Bank0::ACCEL_CONFIG0)
     }
 
     /// Set the range of the accelerometer
     pub fn set_accel_range(&mut self, range: AccelRange) -> Result<(), Error<E>> {
-        self.update_reg(&Bank0::ACCEL_CONFIG0, range.bits(), AccelRange::BITMASK)
+        self.update_field(&Bank0::ACCEL_CONFIG0, range)?;
+        self.config.accel_range = range;
+
+        Ok(())
     }
 
     /// Return the currently configured gyroscope range
     pub fn gyro_range(&mut self) -> Result<GyroRange, Error<E>> {
         // `GYRO_UI_FS_SEL` occupies bits 6:5 in the register
-        let fs_sel = self.read_reg(&Bank0::GYRO_CONFIG0)? >> 5;
-        let range = GyroRange::try_from(fs_sel)?;
-
-        Ok(range)
+        self.read_field(&Bank0::GYRO_CONFIG0)
     }
 
     /// Set the range of the gyro
     pub fn set_gyro_range(&mut self, range: GyroRange) -> Result<(), Error<E>> {
-        self.update_reg(&Bank0::GYRO_CONFIG0, range.bits(), GyroRange::BITMASK)
+        self.update_field(&Bank0::GYRO_CONFIG0, range)?;
+        self.config.gyro_range = range;
+
+        Ok(())
     }
 
     /// Return the currently configured output data rate for the gyroscope
     pub fn gyro_odr(&mut self) -> Result<GyroOdr, Error<E>> {
         // `GYRO_ODR` occupies bits 3:0 in the register
-        let odr = self.read_reg(&Bank0::GYRO_CONFIG0)? & 0xF;
-        let odr = GyroOdr::try_from(odr)?;
-
-        Ok(odr)
+        self.read_field(&Bank0::GYRO_CONFIG0)
     }
 
     /// Set the output data rate of the gyroscope
+    ///
+    /// When the FIFO holds both accelerometer and gyroscope data, the sensor
+    /// with the lower output data rate repeats its samples; see
+    /// [`Icm42670::effective_fifo_rate`].
     pub fn set_gyro_odr(&mut self, odr: GyroOdr) -> Result<(), Error<E>> {
-        self.update_reg(&Bank0::GYRO_CONFIG0, odr.bits(), GyroOdr::BITMASK)
+        if !odr.is_valid_for(self.config.power_mode) {
+            return Err(Error::SensorError(SensorError::InvalidConfig {
+                reason: "gyroscope ODR is not supported in the current power mode",
+            }));
+        }
+
+        self.update_field(&Bank0::GYRO_CONFIG0, odr)?;
+        self.config.gyro_odr = odr;
+
+        Ok(())
     }
 
     pub fn gyro_bandwith(&mut self) -> Result<GyroBw, Error<E>> {
         // `GYRO_UI_FILT_BW` occupies bits 2:0 in the register
-        let bw_sel = self.read_reg(&Bank0::GYRO_CONFIG1)? & 0x07;
-        let bw = GyroBw::try_from(bw_sel)?;
-
-        Ok(bw)
+        self.read_field(&Bank0::GYRO_CONFIG1)
     }
 
     /// Set the gyro_bandwith filter of the gyro
     pub fn set_gyro_bw(&mut self, range: GyroBw) -> Result<(), Error<E>> {
-        self.update_reg(&Bank0::GYRO_CONFIG1, range.bits(), GyroBw::BITMASK)
+        self.update_field(&Bank0::GYRO_CONFIG1, range)?;
+        self.config.gyro_bw = range;
+
+        Ok(())
     }
 
     /// Return the currently configured output data rate for the accelerometer
     pub fn accel_odr(&mut self) -> Result<AccelOdr, Error<E>> {
         // `ACCEL_ODR` occupies bits 3:0 in the register
-        let odr = self.read_reg(&Bank0::ACCEL_CONFIG0)? & 0xF;
-        let odr = AccelOdr::try_from(odr)?;
-
-        Ok(odr)
+        self.read_field(&Bank0::ACCEL_CONFIG0)
     }
 
     /// Set the output data rate of the accelerometer
+    ///
+    /// When the FIFO holds both accelerometer and gyroscope data, the sensor
+    /// with the lower output data rate repeats its samples; see
+    /// [`Icm42670::effective_fifo_rate`]. Rates which the accelerometer
+    /// cannot run at in the current power mode are rejected with
+    /// [`SensorError::InvalidConfig`]; see [`AccelOdr::is_valid_for`]. To
+    /// change the power mode and rate together, use
+    /// [`Icm42670::apply_config`].
     pub fn set_accel_odr(&mut self, odr: AccelOdr) -> Result<(), Error<E>> {
-        self.update_reg(&Bank0::ACCEL_CONFIG0, odr.bits(), AccelOdr::BITMASK)
+        if !odr.is_valid_for(self.config.power_mode) {
+            return Err(Error::SensorError(SensorError::InvalidConfig {
+                reason: "accelerometer ODR is not supported in the current power mode",
+            }));
+        }
+
+        self.update_field(&Bank0::ACCEL_CONFIG0, odr)?;
+        self.config.accel_odr = odr;
+
+        Ok(())
     }
 
     pub fn accel_bandwith(&mut self) -> Result<AccelBw, Error<E>> {
         // `ACCEL_UI_FILT_BW` occupies bits 2:0 in the register
-        let bw_sel = self.read_reg(&Bank0::ACCEL_CONFIG1)? & 0x07;
-        let bw = AccelBw::try_from(bw_sel)?;
-
-        Ok(bw)
+        self.read_field(&Bank0::ACCEL_CONFIG1)
     }
 
     /// Set the accel_bandwith filter of the accel-meter
     pub fn set_accel_bw(&mut self, range: AccelBw) -> Result<(), Error<E>> {
-        self.update_reg(&Bank0::ACCEL_CONFIG1, range.bits(), AccelBw::BITMASK)
+        self.update_field(&Bank0::ACCEL_CONFIG1, range)?;
+        self.config.accel_bw = range;
+
+        Ok(())
+    }
+
+    /// Return the number of measurements averaged into each accelerometer
+    /// sample in low-power mode
+    pub fn accel_avg(&mut self) -> Result<AccelAvg, Error<E>> {
+        // `ACCEL_UI_AVG` occupies bits 6:4 in the register
+        self.read_field(&Bank0::ACCEL_CONFIG1)
+    }
+
+    /// Set the number of measurements averaged into each accelerometer sample
+    ///
+    /// Averaging only applies in [`PowerMode::AccelLowPower`], so
+    /// [`SensorError::InvalidConfig`] is returned in any other power mode
+    /// rather than silently having no effect.
+    pub fn set_accel_avg(&mut self, avg: AccelAvg) -> Result<(), Error<E>> {
+        if self.config.power_mode != PowerMode::AccelLowPower {
+            return Err(Error::SensorError(SensorError::InvalidConfig {
+                reason: "accelerometer averaging only applies in low-power mode",
+            }));
+        }
+
+        self.update_field(&Bank0::ACCEL_CONFIG1, avg)?;
+        self.accel_avg = avg;
+
+        Ok(())
+    }
+
+    /// Approximate supply current, in microamps, drawn in the cached power
+    /// mode
+    ///
+    /// This is a lookup of the typical figures in the datasheet's electrical
+    /// characteristics, with no bus access, for comparing the trade-offs
+    /// between modes in a power budget; it is no substitute for measuring the
+    /// current of a particular board. In the low-noise modes the current does
+    /// not depend on the output data rate. In low-power mode the
+    /// accelerometer is only powered while it takes its measurements, so the
+    /// current grows with the output data rate and the number of measurements
+    /// averaged into each sample.
+    pub fn estimated_current_ua(&self) -> u32 {
+        const SLEEP_UA: u32 = 3;
+        const IDLE_UA: u32 = 35;
+        const GYRO_STANDBY_UA: u32 = 250;
+        const GYRO_LN_UA: u32 = 350;
+        const ACCEL_LN_UA: u32 = 200;
+        // Charge drawn by each measurement in low-power mode, in nanoamp
+        // seconds
+        const ACCEL_LP_MEASUREMENT_NA_S: f32 = 40.0;
+
+        match self.config.power_mode {
+            PowerMode::Sleep => SLEEP_UA,
+            PowerMode::Idle => IDLE_UA,
+            PowerMode::Standby => IDLE_UA + GYRO_STANDBY_UA,
+            PowerMode::AccelLowPower => {
+                let measurements = self.config.accel_odr.as_f32() * self.accel_avg.count() as f32;
+
+                SLEEP_UA + math::round(measurements * ACCEL_LP_MEASUREMENT_NA_S / 1000.0) as u32
+            }
+            PowerMode::AccelLowNoise => IDLE_UA + ACCEL_LN_UA,
+            PowerMode::GyroLowNoise => IDLE_UA + GYRO_LN_UA,
+            PowerMode::SixAxisLowNoise => IDLE_UA + GYRO_LN_UA + ACCEL_LN_UA,
+        }
+    }
+
+    /// Return the clock which duty-cycles the accelerometer in low-power
+    /// mode
+    pub fn accel_lp_clock(&mut self) -> Result<AccelLpClock, Error<E>> {
+        // `ACCEL_LP_CLK_SEL` occupies bit 7 in the register
+        self.read_field(&Bank0::PWR_MGMT0)
+    }
+
+    /// Select the clock which duty-cycles the accelerometer in low-power mode
+    ///
+    /// See [`AccelLpClock`] for the tradeoff between current and jitter. The
+    /// MREG banks cannot be accessed while the wake-up oscillator is in use,
+    /// so select [`AccelLpClock::Rc`] before using the APEX features in
+    /// low-power mode. [`SensorError::InvalidConfig`] is returned unless the
+    /// device is in [`PowerMode::AccelLowPower`].
+    pub fn set_accel_lp_clock(&mut self, clock: AccelLpClock) -> Result<(), Error<E>> {
+        if self.config.power_mode != PowerMode::AccelLowPower {
+            return Err(Error::SensorError(SensorError::InvalidConfig {
+                reason: "the accelerometer clock only applies in low-power mode",
+            }));
+        }
+
+        self.update_field(&Bank0::PWR_MGMT0, clock)
+    }
+
+    /// Return the source of the internal clock
+    pub fn clock_source(&mut self) -> Result<ClockSource, Error<E>> {
+        // `CLKSEL` occupies bits 1:0 in the register
+        self.read_field(&Bank0::INTF_CONFIG1)
+    }
+
+    /// Select the source of the internal clock
+    ///
+    /// The default, [`ClockSource::Pll`], already selects the PLL whenever
+    /// the gyroscope is enabled, which it requires. The RC oscillator is less
+    /// stable, and [`ClockSource::Disabled`] stops every sensor and the
+    /// MREG bank accesses until another source is selected.
+    pub fn set_clock_source(&mut self, source: ClockSource) -> Result<(), Error<E>> {
+        self.update_field(&Bank0::INTF_CONFIG1, source)
+    }
+
+    /// Return the byte order of the sensor data and the FIFO count
+    pub fn data_endianness(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Select the byte order of the sensor data and the FIFO count
+    ///
+    /// The device powers up reporting its data big-endian, which the driver
+    /// assumes until this is called. Every method which reads the data
+    /// registers or the FIFO count, and [`Icm42670::read_fifo_records`],
+    /// decodes the data in the selected order. Raw FIFO data read by
+    /// [`Icm42670::read_fifo`] is returned as the device reports it, and
+    /// must be converted with [`swap_fifo_byte_order`] before it is passed to
+    /// [`parse_fifo_records`] or the other parsers if little-endian.
+    pub fn set_data_endianness(&mut self, order: ByteOrder) -> Result<(), Error<E>> {
+        // `FIFO_COUNT_ENDIAN` occupies bit 5 and `SENSOR_DATA_ENDIAN` bit 4
+        // in the register, and are set for big-endian
+        let bits = match order {
+            ByteOrder::BigEndian => 0b0011_0000,
+            ByteOrder::LittleEndian => 0b0000_0000,
+        };
+        self.update_reg(&Bank0::INTF_CONFIG0, bits, 0b0011_0000)?;
+        self.byte_order = order;
+
+        Ok(())
+    }
+
+    /// Has the PLL locked since this was last called?
+    ///
+    /// The device reports that its PLL is ready as a one-shot interrupt
+    /// condition, once after each time it starts, so this returns `true` only
+    /// for the first read after [`ClockSource::Pll`] switches to it. Reading
+    /// `INT_STATUS` clears its other latched bits; a FIFO overflow is latched
+    /// by the driver, as for [`Icm42670::fifo_overflowed`].
+    pub fn pll_ready(&mut self) -> Result<bool, Error<E>> {
+        // `PLL_RDY_INT` occupies bit 5 in the register
+        let status = self.read_status_reg(Bank0::INT_STATUS)?;
+
+        Ok(status & 0b0010_0000 != 0)
+    }
+
+    /// Return the rate, in Hz, at which packets containing both accelerometer
+    /// and gyroscope data are pushed into the FIFO
+    ///
+    /// Packets are produced at the higher of the two output data rates, so if
+    /// the rates differ then the slower sensor's values are repeated across
+    /// consecutive packets. The rate is computed from the cached
+    /// configuration, without accessing the bus.
+    pub fn effective_fifo_rate(&self) -> f32 {
+        let accel = self.config.accel_odr.as_f32();
+        let gyro = self.config.gyro_odr.as_f32();
+
+        accel.max(gyro)
+    }
+
+    /// Reset and initialize the DMP, which runs the APEX features
+    ///
+    /// This must have succeeded before any APEX feature can be enabled. The
+    /// accelerometer must be running, as the DMP requires the internal clock.
+    pub fn init_dmp(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        // `DMP_MEM_RESET_EN` occupies bit 0 in the register
+        // `DMP_INIT_EN` occupies bit 2 in the register
+        //
+        // Both bits are cleared by the device once the operation has completed.
+        const DMP_MEM_RESET_EN: u8 = 0b0000_0001;
+        const DMP_INIT_EN: u8 = 0b0000_0100;
+
+        self.dmp_initialized = false;
+
+        for (bit, operation) in [
+            (DMP_MEM_RESET_EN, "DMP memory reset"),
+            (DMP_INIT_EN, "DMP initialization"),
+        ] {
+            self.update_reg(&Bank0::APEX_CONFIG0, bit, bit)?;
+            delay_ms(delay, 1);
+
+            // Allow up to 50ms for the operation to complete.
+            self.poll_until(delay, operation, 500, 100, |me| {
+                Ok(me.read_reg(&Bank0::APEX_CONFIG0)? & bit == 0)
+            })?;
+        }
+
+        self.dmp_initialized = true;
+        self.step_count_taken = 0;
+
+        Ok(())
     }
 
     /// Enable pedometer of APEX functions
+    ///
+    /// The DMP must first have been initialized using
+    /// [`Icm42670::init_dmp`].
     pub fn ped_ena(&mut self, enable: bool) -> Result<(), Error<E>> {
-        let mut bits: u8 = 0;
-        if enable == true {
-            bits = 0b0000_1000
-        }
+        self.require_dmp()?;
+
+        let bits: u8 = if enable { 0b0000_1000 } else { 0 };
 
-        self.update_reg(&Bank0::APEX_CONFIG0, 0b0000_0100, 0b0000_0100)?;
         self.update_reg(&Bank0::APEX_CONFIG1, bits, 0b0000_1000)
     }
 
@@ -338,14 +1625,16 @@ where
 
     /// read current fifo buffer level, available to read
     pub fn read_fifo_cnt(&mut self) -> Result<u16, Error<E>> {
-        let fifo_cnt = self.read_reg_u16(&Bank0::FIFO_COUNTH, &Bank0::FIFO_COUNTL)?;
-        Ok(fifo_cnt)
+        let mut regs = [0u8; 2];
+        self.read_regs(&Bank0::FIFO_COUNTH, &mut regs)?;
+
+        Ok(self.byte_order.u16_from_bytes(regs))
     }
 
     pub fn set_fifo_mode(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
         self.write_reg(&Bank0::FIFO_CONFIG1, 0)?;
-        let _res = self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::FIFO_CONFIG5, 3)?;
-        Ok(())
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::FIFO_CONFIG5, 3)?;
+        self.set_fifo_packet(FifoPacketType::Packet3)
     }
 
     pub fn read_mreg1(
@@ -357,19 +1646,58 @@ where
         Ok(read_val)
     }
 
-    // -----------------------------------------------------------------------
-    // development use temporare functions
+    /// Read the User Bank 0 register at `addr`
+    ///
+    /// This provides access to features which the driver does not yet
+    /// support. Reading some registers, such as the interrupt status
+    /// registers, clears them, which the driver will then not observe.
+    pub fn read_register(&mut self, addr: u8) -> Result<u8, Error<E>> {
+        self.read_reg(&addr)
+    }
 
-    pub fn read_fifo(&mut self, buffer: &mut [u8]) -> Result<u8, Error<E>> {
-        //let mut buffer = [0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8];
-        let adr: [u8; 1] = [0x3F];
-        self.i2c
-            .write_read(self.address as u8, &adr, buffer)
-            .map_err(|e| Error::BusError(e))?;
+    /// Write `value` to the User Bank 0 register at `addr`
+    ///
+    /// This provides access to features which the driver does not yet
+    /// support. The driver's cached configuration is not updated, so writing
+    /// a register which the driver also manages, such as `ACCEL_CONFIG0`,
+    /// leaves the cache inconsistent with the device.
+    pub fn write_register(&mut self, addr: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_reg(&addr, value)
+    }
 
-        Ok(buffer[0])
+    /// Read the register at `addr` in one of the MREG banks
+    ///
+    /// The bank is selected through the `BLK_SEL_R` and `MADDR_R` registers,
+    /// and afterwards both `BLK_SEL_R` and `BLK_SEL_W` are reset to 0, as the
+    /// device requires before any other register is accessed. As with every
+    /// MREG access, the internal clock must be running, so this fails in
+    /// [`PowerMode::Sleep`].
+    pub fn read_bank_register(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+        bank: RegisterBank,
+        addr: u8,
+    ) -> Result<u8, Error<E>> {
+        self.read_mreg(delay, bank, &addr)
+    }
+
+    /// Write `value` to the register at `addr` in one of the MREG banks
+    ///
+    /// See [`Icm42670::read_bank_register`] and
+    /// [`Icm42670::write_register`] for the caveats which apply.
+    pub fn write_bank_register(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+        bank: RegisterBank,
+        addr: u8,
+        value: u8,
+    ) -> Result<(), Error<E>> {
+        self.write_mreg(delay, bank, &addr, value)
     }
 
+    // -----------------------------------------------------------------------
+    // development use temporare functions
+
     // pub fn read_fifo(&mut self, addr: u8, buffer: &mut [u8]) -> Result<u8, Error<E>> {
     //     //let mut buffer = [0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8];
     //     let adr: [u8; 1] = [addr];
@@ -383,8 +1711,8 @@ where
     pub fn readreg(&mut self, addr: u8) -> Result<u8, Error<E>> {
         let mut buffer = [0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8];
         let adr: [u8; 1] = [addr];
-        self.i2c
-            .write_read(self.address as u8, &adr, &mut buffer)
+        self.iface
+            .read(adr[0], &mut buffer)
             .map_err(|e| Error::BusError(e))?;
 
         Ok(buffer[2])
@@ -405,7 +1733,7 @@ where
         // See "ACCESSING MREG1, MREG2 AND MREG3 REGISTERS" (page 40)
 
         // Wait until the internal clock is running prior to writing.
-        while self.read_reg(&Bank0::MCLK_RDY)? != 0x9 {}
+        self.poll_until(delay, "MCLK ready", 1000, 10, |me| me.mclk_ready())?;
 
         // Select the appropriate block and set the register address to read from.
         self.write_reg(&Bank0::BLK_SEL_R, bank.blk_sel())?;
@@ -436,7 +1764,7 @@ where
         // See "ACCESSING MREG1, MREG2 AND MREG3 REGISTERS" (page 40)
 
         // Wait until the internal clock is running prior to writing.
-        while self.read_reg(&Bank0::MCLK_RDY)? != 0x9 {}
+        self.poll_until(delay, "MCLK ready", 1000, 10, |me| me.mclk_ready())?;
 
         // Select the appropriate block and set the register address to write to.
         self.write_reg(&Bank0::BLK_SEL_W, bank.blk_sel())?;
@@ -453,17 +1781,315 @@ where
         Ok(())
     }
 
+    /// Read the normalized accelerometer data for each of the three axes.
+    fn read_accel_norm(&mut self) -> Result<F32x3, Error<E>> {
+        let (filtered, _) = self.read_accel_filtered()?;
+
+        Ok(filtered)
+    }
+
+    /// Read the normalized accelerometer data for each of the three axes, both
+    /// with and without smoothing applied.
+    fn read_accel_filtered(&mut self) -> Result<(F32x3, F32x3), Error<E>> {
+        let scale = self.config.accel_range.scale_factor();
+
+        // Scale the raw Accelerometer data using the appropriate factor based on the
+        // configured range.
+        let raw = self.read_accel_raw()?;
+        let x = raw.x as f32 / scale;
+        let y = raw.y as f32 / scale;
+        let z = raw.z as f32 / scale;
+
+        let unfiltered = subtract(F32x3::new(x, y, z), self.software_bias.accel);
+        let filtered = self.accel_ema.update(unfiltered);
+
+        Ok((filtered, unfiltered))
+    }
+
+    /// Read the normalized gyro data for each of the three axes, both with and
+    /// without smoothing applied, then apply the deadband to each.
+    fn read_gyro_filtered(&mut self) -> Result<(F32x3, F32x3), Error<E>> {
+        let scale = self.config.gyro_range.scale_factor();
+
+        // Scale the raw Gyroscope data using the appropriate factor based on the
+        // configured range.
+        let raw = self.gyro_raw()?;
+        let x = raw.x as f32 / scale;
+        let y = raw.y as f32 / scale;
+        let z = raw.z as f32 / scale;
+
+        let bias = self.gyro_bias_now()?;
+        let unfiltered = subtract(F32x3::new(x, y, z), bias);
+        let filtered = self.gyro_ema.update(unfiltered);
+
+        Ok((
+            self.apply_gyro_deadband(filtered),
+            self.apply_gyro_deadband(unfiltered),
+        ))
+    }
+
+    /// Zero out each axis of a normalized gyro reading which falls within the
+    /// deadband.
+    fn apply_gyro_deadband(&self, dps: F32x3) -> F32x3 {
+        let deadband = |v: f32| {
+            if v < self.gyro_deadband && v > -self.gyro_deadband {
+                0.0
+            } else {
+                v
+            }
+        };
+
+        F32x3::new(deadband(dps.x), deadband(dps.y), deadband(dps.z))
+    }
+
+    /// Repeatedly evaluate `done`, waiting `interval_us` between attempts,
+    /// until it returns `true`.
+    ///
+    /// If `done` has not returned `true` after `max_polls` attempts then
+    /// [`SensorError::Timeout`] is returned for the named operation.
+    fn poll_until<F>(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+        operation: &'static str,
+        max_polls: u16,
+        interval_us: u8,
+        mut done: F,
+    ) -> Result<(), Error<E>>
+    where
+        F: FnMut(&mut Self) -> Result<bool, Error<E>>,
+    {
+        for _ in 0..max_polls {
+            if done(self)? {
+                return Ok(());
+            }
+
+            delay.delay_us(interval_us);
+        }
+
+        Err(Error::SensorError(SensorError::Timeout { operation }))
+    }
+
+    /// Record the power mode after changing the mode of a single sensor, if
+    /// the resulting combination corresponds to a `PowerMode`.
+    fn update_power_mode_bits(&mut self, bits: u8) {
+        if let Ok(mode) = PowerMode::try_from(bits) {
+            self.config.power_mode = mode;
+        }
+    }
+
+    /// Wait until new sensor data is available.
+    ///
+    /// Allows up to two periods of the slower of the configured output data
+    /// rates before timing out.
+    fn wait_data_ready(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        let odr = self
+            .config
+            .accel_odr
+            .as_f32()
+            .min(self.config.gyro_odr.as_f32());
+
+        self.wait_data_ready_for(delay, (2_000_000.0 / odr) as u32)
+    }
+
+    /// Wait up to `timeout_us` microseconds until new sensor data is
+    /// available.
+    fn wait_data_ready_for(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+        timeout_us: u32,
+    ) -> Result<(), Error<E>> {
+        const INTERVAL_US: u8 = 250;
+
+        let max_polls = (timeout_us / INTERVAL_US as u32).min(u16::MAX as u32 - 1) as u16 + 1;
+
+        self.poll_until(delay, "data ready", max_polls, INTERVAL_US, |me| {
+            me.data_ready()
+        })
+    }
+
+    /// Wait until the device has finished initializing after a software reset.
+    ///
+    /// The reset takes 1ms, so allows up to 5ms before timing out. The device
+    /// does not respond during the reset itself, so the first read is made
+    /// after 1ms.
+    fn wait_reset_done(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        const INTERVAL_US: u8 = 250;
+
+        delay_ms(delay, 1);
+        self.select_interface_mode()?;
+        self.poll_until(delay, "soft reset", 16, INTERVAL_US, |me| me.reset_done())
+    }
+
+    /// Configure the device for the interface in use, if it requires other
+    /// than the reset configuration.
+    fn select_interface_mode(&mut self) -> Result<(), Error<E>> {
+        match self.iface.device_config() {
+            Some(value) => self.write_reg(&Bank0::DEVICE_CONFIG, value),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensure that the DMP has been initialized before using an APEX feature.
+    fn require_dmp(&self) -> Result<(), Error<E>> {
+        if self.dmp_initialized {
+            Ok(())
+        } else {
+            Err(Error::SensorError(SensorError::DmpNotInitialized))
+        }
+    }
+
+    /// Ensure that the accelerometer is producing data in the current power
+    /// mode.
+    fn require_accel(&self) -> Result<(), Error<E>> {
+        if self.config.power_mode.accel_enabled() {
+            Ok(())
+        } else {
+            Err(Error::SensorError(SensorError::SensorNotActive {
+                sensor: "accelerometer",
+            }))
+        }
+    }
+
+    /// Ensure that the temperature sensor is producing data in the current
+    /// power mode; it runs only alongside one of the other sensors.
+    fn require_temperature(&self) -> Result<(), Error<E>> {
+        self.require_accel()
+            .or_else(|_| self.require_gyro())
+            .map_err(|_| {
+                Error::SensorError(SensorError::SensorNotActive {
+                    sensor: "temperature sensor",
+                })
+            })
+    }
+
+    /// Ensure that the gyroscope is producing data in the current power mode;
+    /// in standby only its drive circuitry is running.
+    fn require_gyro(&self) -> Result<(), Error<E>> {
+        if matches!(
+            self.config.power_mode,
+            PowerMode::GyroLowNoise | PowerMode::SixAxisLowNoise
+        ) {
+            Ok(())
+        } else {
+            Err(Error::SensorError(SensorError::SensorNotActive {
+                sensor: "gyroscope",
+            }))
+        }
+    }
+
+    /// Read every sensor in a single transaction, scaling the data using the
+    /// cached ranges.
+    fn read_sample_burst(&mut self) -> Result<Sample, Error<E>> {
+        self.require_accel()?;
+        self.require_gyro()?;
+
+        // TEMP_DATA1/0, ACCEL_DATA_X1 through ACCEL_DATA_Z0, and GYRO_DATA_X1
+        // through GYRO_DATA_Z0 are contiguous
+        let mut regs = [0u8; 14];
+        self.read_regs(&Bank0::TEMP_DATA1, &mut regs)?;
+
+        let order = self.byte_order;
+        let value = |i: usize| order.i16_from_bytes([regs[i], regs[i + 1]]) as f32;
+        let accel_scale = self.config.accel_range.scale_factor();
+        let gyro_scale = self.config.gyro_range.scale_factor();
+        let temp = (value(0) / 128.0) + 25.0;
+
+        Ok(Sample {
+            accel: subtract(
+                F32x3::new(
+                    value(2) / accel_scale,
+                    value(4) / accel_scale,
+                    value(6) / accel_scale,
+                ),
+                self.software_bias.accel,
+            ),
+            gyro: subtract(
+                F32x3::new(
+                    value(8) / gyro_scale,
+                    value(10) / gyro_scale,
+                    value(12) / gyro_scale,
+                ),
+                self.gyro_bias_at(temp),
+            ),
+            temp,
+        })
+    }
+
+    /// Read the raw accelerometer data for each of the three axes.
+    fn read_accel_raw(&mut self) -> Result<I16x3, Error<E>> {
+        self.require_accel()?;
+
+        let raw = self.read_vector(&Bank0::ACCEL_DATA_X1)?;
+
+        self.accel_repeated = self.last_accel_raw == Some(raw);
+        self.last_accel_raw = Some(raw);
+        self.accel_repeats = count_repeat(self.accel_repeats, self.accel_repeated);
+        self.check_stuck(self.accel_repeats, "accelerometer")?;
+
+        Ok(raw)
+    }
+
+    /// Return [`SensorError::StuckData`] if `repeats` exceeds the stuck
+    /// detection threshold.
+    fn check_stuck(&self, repeats: u16, sensor: &'static str) -> Result<(), Error<E>> {
+        match self.stuck_threshold {
+            Some(threshold) if repeats > threshold => {
+                Err(Error::SensorError(SensorError::StuckData { sensor }))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Read a register at the provided address.
     fn read_reg(&mut self, reg: &dyn Register) -> Result<u8, Error<E>> {
         let mut buffer = [0u8];
-        self.i2c
-            .write_read(self.address as u8, &[reg.addr()], &mut buffer)
-            .map_err(|e| Error::BusError(e))?;
+        self.bus_read(reg.addr(), &mut buffer)?;
 
         Ok(buffer[0])
     }
 
-    /// Read two registers and combine them into a single value.
+    /// Read consecutive registers, starting at the provided address.
+    fn read_regs(&mut self, reg: &dyn Register, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        self.read_chunked(reg.addr(), true, buffer)
+    }
+
+    /// Fill `buffer` from the register at `addr`, splitting the read into
+    /// several transactions if it exceeds the maximum transaction length. When
+    /// `increment` is set, each transaction starts at the register following
+    /// the last one read.
+    fn read_chunked(
+        &mut self,
+        addr: u8,
+        increment: bool,
+        buffer: &mut [u8],
+    ) -> Result<(), Error<E>> {
+        let len = self.max_transaction_len.unwrap_or(buffer.len()).max(1);
+        let mut addr = addr;
+
+        for chunk in buffer.chunks_mut(len) {
+            self.bus_read(addr, chunk)?;
+
+            if increment {
+                addr = addr.wrapping_add(chunk.len() as u8);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fill `buffer` from the FIFO, without checking how many bytes it holds.
+    fn read_fifo_data(&mut self, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        // Reading `FIFO_DATA` does not advance the register address, so every
+        // chunk is read from the same register.
+        self.read_chunked(Bank0::FIFO_DATA as u8, false, buffer)
+    }
+
+    /// Read two data registers and combine them into a single value, in the
+    /// configured byte order.
     fn read_reg_i16(
         &mut self,
         reg_hi: &dyn Register,
@@ -472,11 +2098,28 @@ where
         let data_lo = self.read_reg(reg_lo)?;
         let data_hi = self.read_reg(reg_hi)?;
 
-        let data = i16::from_be_bytes([data_hi, data_lo]);
+        let data = self.byte_order.i16_from_bytes([data_hi, data_lo]);
 
         Ok(data)
     }
 
+    /// Read the X, Y, and Z values of a sensor from six consecutive
+    /// registers, starting at the provided address, in the configured byte
+    /// order.
+    ///
+    /// The registers are read in a single transaction, unless
+    /// [`Icm42670::set_max_transaction_len`] requires otherwise, so the axes
+    /// cannot be torn across a sensor update.
+    fn read_vector(&mut self, reg: &dyn Register) -> Result<I16x3, Error<E>> {
+        let mut regs = [0u8; 6];
+        self.read_regs(reg, &mut regs)?;
+
+        let order = self.byte_order;
+        let value = |i: usize| order.i16_from_bytes([regs[i], regs[i + 1]]);
+
+        Ok(I16x3::new(value(0), value(2), value(4)))
+    }
+
     /// Read two registers and combine them into a single value.
     fn read_reg_u16(
         &mut self,
@@ -491,17 +2134,175 @@ where
         Ok(data)
     }
 
+    /// Set consecutive registers, starting at the provided address, to the
+    /// given values.
+    ///
+    /// At most 15 registers may be written at once.
+    fn write_regs(&mut self, reg: &dyn Register, values: &[u8]) -> Result<(), Error<E>> {
+        if reg.read_only() {
+            return Err(Error::SensorError(SensorError::WriteToReadOnly));
+        }
+
+        // Each transaction begins with the address of its first register
+        let len = self
+            .max_transaction_len
+            .map_or(values.len(), |len| len - 1)
+            .max(1);
+        let mut addr = reg.addr();
+
+        for chunk in values.chunks(len) {
+            let mut buffer = [0u8; 16];
+            buffer[0] = addr;
+            buffer[1..=chunk.len()].copy_from_slice(chunk);
+
+            self.bus_write(&buffer[..=chunk.len()])?;
+
+            addr = addr.wrapping_add(chunk.len() as u8);
+        }
+
+        Ok(())
+    }
+
     /// Set a register at the provided address to a given value.
     fn write_reg(&mut self, reg: &dyn Register, value: u8) -> Result<(), Error<E>> {
         if reg.read_only() {
             Err(Error::SensorError(SensorError::WriteToReadOnly))
         } else {
-            self.i2c
-                .write(self.address as u8, &[reg.addr(), value])
-                .map_err(|e| Error::BusError(e))
+            self.bus_write(&[reg.addr(), value])
+        }
+    }
+
+    /// Fill `buffer` from the register at `addr` in a single transaction,
+    /// retrying as configured by [`Icm42670::set_bus_retries`].
+    fn bus_read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        let mut result = self.iface.read(addr, buffer);
+        for _ in 0..self.bus_retries {
+            if result.is_ok() {
+                break;
+            }
+            result = self.iface.read(addr, buffer);
+        }
+
+        result.map_err(|e| Error::BusError(e))
+    }
+
+    /// Write `data` to the device in a single transaction, retrying as
+    /// configured by [`Icm42670::set_bus_retries`].
+    fn bus_write(&mut self, data: &[u8]) -> Result<(), Error<E>> {
+        let mut result = self.iface.write(data);
+        for _ in 0..self.bus_retries {
+            if result.is_ok() {
+                break;
+            }
+            result = self.iface.write(data);
+        }
+
+        result.map_err(|e| Error::BusError(e))
+    }
+
+    /// Read a single field of the register at the provided address.
+    ///
+    /// Only the bits covered by the field's bitmask are decoded, so reserved
+    /// bits elsewhere in the register cannot cause a spurious
+    /// [`SensorError::InvalidDiscriminant`].
+    fn read_field<F>(&mut self, reg: &dyn Register) -> Result<F, Error<E>>
+    where
+        F: Bitfield + TryFrom<u8, Error = SensorError>,
+    {
+        let value = self.read_reg(reg)?;
+        let field = F::try_from(extract_bitfield::<F>(value))?;
+
+        Ok(field)
+    }
+
+    /// Update a single field of the register at the provided address.
+    ///
+    /// The register's current value is read in and only the bits covered by
+    /// the field's bitmask are replaced before writing it back.
+    fn update_field<F: Bitfield>(&mut self, reg: &dyn Register, field: F) -> Result<(), Error<E>> {
+        if reg.read_only() {
+            Err(Error::SensorError(SensorError::WriteToReadOnly))
+        } else {
+            let current = self.read_reg(reg)?;
+            let value = apply_bitfield(current, field);
+            self.write_reg(reg, value)?;
+
+            if self.verify_writes {
+                self.verify_regs(reg, &[value])?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Write the sensor configuration registers and then the power mode,
+    /// verifying each.
+    fn write_config_verified(
+        &mut self,
+        regs: &[u8; 5],
+        mode: PowerMode,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        self.write_regs(&Bank0::GYRO_CONFIG0, regs)?;
+        self.verify_regs(&Bank0::GYRO_CONFIG0, regs)?;
+
+        self.set_power_mode_staged(mode, delay)?;
+        let got = self.read_reg(&Bank0::PWR_MGMT0)? & PowerMode::BITMASK;
+        if got != mode.bits() {
+            return Err(Error::SensorError(SensorError::WriteVerifyFailed {
+                reg: Bank0::PWR_MGMT0.addr(),
+                expected: mode.bits(),
+                got,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Update two fields sharing a register using a single read and write.
+    fn update_fields<F: Bitfield, G: Bitfield>(
+        &mut self,
+        reg: &dyn Register,
+        first: F,
+        second: G,
+    ) -> Result<(), Error<E>> {
+        if reg.read_only() {
+            Err(Error::SensorError(SensorError::WriteToReadOnly))
+        } else {
+            let current = self.read_reg(reg)?;
+            let value = apply_bitfield(apply_bitfield(current, first), second);
+            self.write_reg(reg, value)?;
+
+            if self.verify_writes {
+                self.verify_regs(reg, &[value])?;
+            }
+
+            Ok(())
         }
     }
 
+    /// Ensure that consecutive registers, starting at the provided address,
+    /// hold the expected values.
+    ///
+    /// At most 16 registers may be verified at once.
+    fn verify_regs(&mut self, reg: &dyn Register, expected: &[u8]) -> Result<(), Error<E>> {
+        let mut buffer = [0u8; 16];
+        let actual = &mut buffer[..expected.len()];
+        self.read_regs(reg, actual)?;
+
+        for (offset, (&expected, &got)) in expected.iter().zip(actual.iter()).enumerate() {
+            if expected != got {
+                return Err(Error::SensorError(SensorError::WriteVerifyFailed {
+                    reg: reg.addr() + offset as u8,
+                    expected,
+                    got,
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Update the register at the provided address.
     ///
     /// Rather than overwriting any active bits in the register, we first read
@@ -528,6 +2329,39 @@ pub enum FifoPacketType {
     Packet4,
 }
 
+impl FifoPacketType {
+    /// The format of the FIFO packet beginning with `header`, or `None` if
+    /// the header indicates that the FIFO is empty
+    pub fn from_header(header: u8) -> Option<Self> {
+        use FifoPacketType::*;
+
+        // `HEADER_MSG` occupies bit 7, `HEADER_ACCEL` bit 6, `HEADER_GYRO`
+        // bit 5, and `HEADER_20` bit 4 of the header
+        if header & 0b1000_0000 != 0 {
+            None
+        } else if header & 0b0001_0000 != 0 {
+            Some(Packet4)
+        } else {
+            match header & 0b0110_0000 {
+                0b0110_0000 => Some(Packet3),
+                0b0010_0000 => Some(Packet2),
+                _ => Some(Packet1),
+            }
+        }
+    }
+
+    /// Size of a single packet of this type, in bytes
+    pub fn size(self) -> usize {
+        use FifoPacketType::*;
+
+        match self {
+            Packet1 | Packet2 => 8,
+            Packet3 => 16,
+            Packet4 => 20,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct FifoDataP1 {
     pub ax: i16,
@@ -537,7 +2371,7 @@ pub struct FifoDataP1 {
 }
 
 impl FifoDataP1 {
-    pub fn to_fifodata_raw(buffer: &mut [u8]) -> Self {
+    pub fn to_fifodata_raw(buffer: &[u8]) -> Self {
         let ax = i16::from_be_bytes([buffer[1], buffer[2]]);
         let ay = i16::from_be_bytes([buffer[3], buffer[4]]);
         let az = i16::from_be_bytes([buffer[5], buffer[6]]);
@@ -556,7 +2390,7 @@ pub struct FifoDataP2 {
 }
 
 impl FifoDataP2 {
-    pub fn to_fifodata_raw(buffer: &mut [u8]) -> Self {
+    pub fn to_fifodata_raw(buffer: &[u8]) -> Self {
         let gx = i16::from_be_bytes([buffer[1], buffer[2]]);
         let gy = i16::from_be_bytes([buffer[3], buffer[4]]);
         let gz = i16::from_be_bytes([buffer[5], buffer[6]]);
@@ -579,7 +2413,7 @@ pub struct FifoDataP3 {
 }
 
 impl FifoDataP3 {
-    pub fn to_fifodata_raw(buffer: &mut [u8]) -> Self {
+    pub fn to_fifodata_raw(buffer: &[u8]) -> Self {
         let ax = i16::from_be_bytes([buffer[1], buffer[2]]);
         let ay = i16::from_be_bytes([buffer[3], buffer[4]]);
         let az = i16::from_be_bytes([buffer[5], buffer[6]]);
@@ -615,76 +2449,22 @@ pub struct FifoDataP4 {
 }
 
 impl FifoDataP4 {
-    pub fn to_fifodata_raw(buffer: &mut [u8]) -> Self {
-        let ax0: u8 = ((buffer[17] & 0xf0) >> 4) | (buffer[2] & 0x0f) << 4;
-        let ax1: u8 = ((buffer[2] & 0xf0) >> 4) | (buffer[1] & 0x0f) << 4;
-        let mut ax2: u8 = 0;
-        let mut ax3: u8 = 0;
-        if (buffer[1] & 0x80) > 0 {
-            ax2 = ((buffer[1] & 0xf0) >> 4) | 0xf0;
-            ax3 = 0xff;
-        } else {
-            ax2 = (buffer[1] & 0xf0) >> 4;
-        }
-        let ax = i32::from_be_bytes([ax3, ax2, ax1, ax0]);
-        let ay0: u8 = ((buffer[18] & 0xf0) >> 4) | (buffer[4] & 0x0f) << 4;
-        let ay1: u8 = ((buffer[4] & 0xf0) >> 4) | (buffer[3] & 0x0f) << 4;
-        let mut ay2: u8 = 0;
-        let mut ay3: u8 = 0;
-        if (buffer[3] & 0x80) > 0 {
-            ay2 = ((buffer[3] & 0xf0) >> 4) | 0xf0;
-            ay3 = 0xff;
-        } else {
-            ay2 = (buffer[3] & 0xf0) >> 4;
-        }
-        let ay = i32::from_be_bytes([ay3, ay2, ay1, ay0]);
-        let az0: u8 = ((buffer[19] & 0xf0) >> 4) | (buffer[6] & 0x0f) << 4;
-        let az1: u8 = ((buffer[6] & 0xf0) >> 4) | (buffer[5] & 0x0f) << 4;
-        let mut az2: u8 = 0;
-        let mut az3: u8 = 0;
-        if (buffer[5] & 0x80) > 0 {
-            az2 = ((buffer[5] & 0xf0) >> 4) | 0xf0;
-            az3 = 0xff;
-        } else {
-            az2 = (buffer[5] & 0xf0) >> 4;
-        }
-        let az = i32::from_be_bytes([az3, az2, az1, az0]);
-
-        let gx0: u8 = (buffer[17] & 0x0f) | (buffer[8] & 0x0f) << 4;
-        let gx1: u8 = ((buffer[8] & 0xf0) >> 4) | (buffer[7] & 0x0f) << 4;
-        let mut gx2: u8 = 0;
-        let mut gx3: u8 = 0;
-        if (buffer[7] & 0x80) > 0 {
-            gx2 = ((buffer[7] & 0xf0) >> 4) | 0xf0;
-            gx3 = 0xff;
-        } else {
-            gx2 = (buffer[7] & 0xf0) >> 4;
-        }
-        let gx = i32::from_be_bytes([gx3, gx2, gx1, gx0]);
-
-        let gy0: u8 = (buffer[18] & 0x0f) | (buffer[10] & 0x0f) << 4;
-        let gy1: u8 = ((buffer[10] & 0xf0) >> 4) | (buffer[9] & 0x0f) << 4;
-        let mut gy2: u8 = 0;
-        let mut gy3: u8 = 0;
-        if (buffer[9] & 0x80) > 0 {
-            gy2 = ((buffer[9] & 0xf0) >> 4) | 0xf0;
-            gy3 = 0xff;
-        } else {
-            gy2 = (buffer[9] & 0xf0) >> 4;
-        }
-        let gy = i32::from_be_bytes([gy3, gy2, gy1, gy0]);
-
-        let gz0: u8 = (buffer[19] & 0x0f) | (buffer[12] & 0x0f) << 4;
-        let gz1: u8 = ((buffer[12] & 0xf0) >> 4) | (buffer[11] & 0x0f) << 4;
-        let mut gz2: u8 = 0;
-        let mut gz3: u8 = 0;
-        if (buffer[11] & 0x80) > 0 {
-            gz2 = ((buffer[11] & 0xf0) >> 4) | 0xf0;
-            gz3 = 0xff;
-        } else {
-            gz2 = (buffer[11] & 0xf0) >> 4;
-        }
-        let gz = i32::from_be_bytes([gz3, gz2, gz1, gz0]);
+    pub fn to_fifodata_raw(buffer: &[u8]) -> Self {
+        // Each value is 20 bits wide: the upper 16 bits are stored big-endian
+        // in the same position as in a 16 byte packet, and the lower 4 bits
+        // in bytes 17 to 19, accelerometer in the upper nibble and gyroscope
+        // in the lower nibble of each. The most significant byte is sign
+        // extended.
+        let value = |offset: usize, low: u8| {
+            ((buffer[offset] as i8 as i32) << 12) | ((buffer[offset + 1] as i32) << 4) | low as i32
+        };
+
+        let ax = value(1, buffer[17] >> 4);
+        let ay = value(3, buffer[18] >> 4);
+        let az = value(5, buffer[19] >> 4);
+        let gx = value(7, buffer[17] & 0x0F);
+        let gy = value(9, buffer[18] & 0x0F);
+        let gz = value(11, buffer[19] & 0x0F);
 
         let t = i16::from_be_bytes([buffer[13], buffer[14]]);
         let ts = u16::from_be_bytes([buffer[15], buffer[16]]);
@@ -711,17 +2491,17 @@ pub struct FifoDataSiP1 {
 }
 
 impl FifoDataSiP1 {
-    pub fn to_fifodata_si(buffer: &mut [u8], ascal: f32) -> Self {
+    pub fn to_fifodata_si(buffer: &[u8], ascal: f32) -> Self {
         let ax = i16::from_be_bytes([buffer[1], buffer[2]]);
         let ay = i16::from_be_bytes([buffer[3], buffer[4]]);
         let az = i16::from_be_bytes([buffer[5], buffer[6]]);
-        let t = i8::from_be_bytes([buffer[13]]);
+        let t = fifo::temperature_8bit(buffer[7]);
 
         Self {
-            ax: (ax as f32) / ascal * GRAVITY,
-            ay: (ay as f32) / ascal * GRAVITY,
-            az: (az as f32) / ascal * GRAVITY,
-            t: (((t as f32) / 128.0) + 25.0),
+            ax: (ax as f32) / ascal * STANDARD_GRAVITY,
+            ay: (ay as f32) / ascal * STANDARD_GRAVITY,
+            az: (az as f32) / ascal * STANDARD_GRAVITY,
+            t,
         }
     }
 }
@@ -735,17 +2515,17 @@ pub struct FifoDataSiP2 {
 }
 
 impl FifoDataSiP2 {
-    pub fn to_fifodata_si(buffer: &mut [u8], gscal: f32) -> Self {
-        let gx = i16::from_be_bytes([buffer[7], buffer[8]]);
-        let gy = i16::from_be_bytes([buffer[9], buffer[10]]);
-        let gz = i16::from_be_bytes([buffer[11], buffer[12]]);
-        let t = i8::from_be_bytes([buffer[13]]);
+    pub fn to_fifodata_si(buffer: &[u8], gscal: f32) -> Self {
+        let gx = i16::from_be_bytes([buffer[1], buffer[2]]);
+        let gy = i16::from_be_bytes([buffer[3], buffer[4]]);
+        let gz = i16::from_be_bytes([buffer[5], buffer[6]]);
+        let t = fifo::temperature_8bit(buffer[7]);
 
         Self {
             gx: ((gx as f32) / gscal) * PI / 180.0,
             gy: ((gy as f32) / gscal) * PI / 180.0,
             gz: ((gz as f32) / gscal) * PI / 180.0,
-            t: (((t as f32) / 128.0) + 25.0),
+            t,
         }
     }
 }
@@ -763,24 +2543,24 @@ pub struct FifoDataSiP3 {
 }
 
 impl FifoDataSiP3 {
-    pub fn to_fifodata_si(buffer: &mut [u8], ascal: f32, gscal: f32) -> Self {
+    pub fn to_fifodata_si(buffer: &[u8], ascal: f32, gscal: f32) -> Self {
         let ax = i16::from_be_bytes([buffer[1], buffer[2]]);
         let ay = i16::from_be_bytes([buffer[3], buffer[4]]);
         let az = i16::from_be_bytes([buffer[5], buffer[6]]);
         let gx = i16::from_be_bytes([buffer[7], buffer[8]]);
         let gy = i16::from_be_bytes([buffer[9], buffer[10]]);
         let gz = i16::from_be_bytes([buffer[11], buffer[12]]);
-        let t = i8::from_be_bytes([buffer[13]]);
+        let t = fifo::temperature_8bit(buffer[13]);
         let ts = u16::from_be_bytes([buffer[14], buffer[15]]);
 
         Self {
-            ax: (ax as f32) / ascal * GRAVITY,
-            ay: (ay as f32) / ascal * GRAVITY,
-            az: (az as f32) / ascal * GRAVITY,
+            ax: (ax as f32) / ascal * STANDARD_GRAVITY,
+            ay: (ay as f32) / ascal * STANDARD_GRAVITY,
+            az: (az as f32) / ascal * STANDARD_GRAVITY,
             gx: ((gx as f32) / gscal) * PI / 180.0,
             gy: ((gy as f32) / gscal) * PI / 180.0,
             gz: ((gz as f32) / gscal) * PI / 180.0,
-            t: (((t as f32) / 128.0) + 25.0),
+            t,
             ts: (ts as f32) / 1_000_000.0,
         }
     }
@@ -800,8 +2580,9 @@ pub struct FifoDataSiP4 {
 
 impl FifoDataSiP4 {
     pub fn to_fifodata_si(raw_data: &FifoDataP4) -> Self {
-        const ascal: f32 = 8192.0 * 4.0;
-        const gscal: f32 = 131.0 * 2.0;
+        // High-resolution mode fixes the full-scale ranges at their maximums.
+        let ascal = AccelRange::G16.scale_factor_hires();
+        let gscal = GyroRange::Deg2000.scale_factor_hires();
 
         let ax = raw_data.ax as f32;
         let ay = raw_data.ay as f32;
@@ -813,37 +2594,52 @@ impl FifoDataSiP4 {
         let ts = raw_data.ts as f32;
 
         Self {
-            ax: (ax as f32) / ascal * GRAVITY,
-            ay: (ay as f32) / ascal * GRAVITY,
-            az: (az as f32) / ascal * GRAVITY,
-            gx: ((gx as f32) / gscal) * PI / 180.0,
-            gy: ((gy as f32) / gscal) * PI / 180.0,
-            gz: ((gz as f32) / gscal) * PI / 180.0,
-            t: (((t as f32) / 128.0) + 25.0),
-            ts: (ts as f32) / 1_000_000.0,
+            ax: ax / ascal * STANDARD_GRAVITY,
+            ay: ay / ascal * STANDARD_GRAVITY,
+            az: az / ascal * STANDARD_GRAVITY,
+            gx: (gx / gscal) * PI / 180.0,
+            gy: (gy / gscal) * PI / 180.0,
+            gz: (gz / gscal) * PI / 180.0,
+            t: ((t / 128.0) + 25.0),
+            ts: ts / 1_000_000.0,
+        }
+    }
+}
+
+/// Update the number of consecutive repeated readings.
+fn count_repeat(repeats: u16, repeated: bool) -> u16 {
+    if repeated {
+        repeats.saturating_add(1)
+    } else {
+        0
+    }
+}
+
+/// Subtract `offset` from each axis of `value`.
+fn subtract(value: F32x3, offset: F32x3) -> F32x3 {
+    F32x3::new(value.x - offset.x, value.y - offset.y, value.z - offset.z)
+}
+
+/// Block for the given number of milliseconds.
+fn delay_ms(delay: &mut dyn DelayUs<u8>, ms: u16) {
+    for _ in 0..ms {
+        for _ in 0..4 {
+            delay.delay_us(250);
         }
     }
 }
 
-impl<I2C, E> Accelerometer for Icm42670<I2C>
+impl<DI, E> Accelerometer for Icm42670<DI>
 where
-    I2C: Write<Error = E> + WriteRead<Error = E>,
+    DI: Interface<Error = E>,
     E: Debug,
 {
     type Error = Error<E>;
 
     fn accel_norm(&mut self) -> Result<F32x3, AccelerometerError<Self::Error>> {
-        let range = self.accel_range()?;
-        let scale = range.scale_factor();
-
-        // Scale the raw Accelerometer data using the appropriate factor based on the
-        // configured range.
-        let raw = self.accel_raw()?;
-        let x = raw.x as f32 / scale;
-        let y = raw.y as f32 / scale;
-        let z = raw.z as f32 / scale;
+        let norm = self.read_accel_norm()?;
 
-        Ok(F32x3::new(x, y, z))
+        Ok(norm)
     }
 
     fn sample_rate(&mut self) -> Result<f32, AccelerometerError<Self::Error>> {
@@ -854,18 +2650,16 @@ where
     }
 }
 
-impl<I2C, E> RawAccelerometer<I16x3> for Icm42670<I2C>
+impl<DI, E> RawAccelerometer<I16x3> for Icm42670<DI>
 where
-    I2C: Write<Error = E> + WriteRead<Error = E>,
+    DI: Interface<Error = E>,
     E: Debug,
 {
     type Error = Error<E>;
 
     fn accel_raw(&mut self) -> Result<I16x3, AccelerometerError<Self::Error>> {
-        let x = self.read_reg_i16(&Bank0::ACCEL_DATA_X1, &Bank0::ACCEL_DATA_X0)?;
-        let y = self.read_reg_i16(&Bank0::ACCEL_DATA_Y1, &Bank0::ACCEL_DATA_Y0)?;
-        let z = self.read_reg_i16(&Bank0::ACCEL_DATA_Z1, &Bank0::ACCEL_DATA_Z0)?;
+        let raw = self.read_accel_raw()?;
 
-        Ok(I16x3::new(x, y, z))
+        Ok(raw)
     }
 }
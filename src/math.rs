@@ -0,0 +1,62 @@
+//! Floating-point operations which are not available in `core`
+//!
+//! These are provided by `micromath` by default, which the `accelerometer`
+//! crate already depends upon. Enabling the `libm` feature uses `libm`
+//! instead, which is slower but more accurate.
+
+#[cfg(not(feature = "libm"))]
+use micromath::F32Ext;
+
+/// Square root of `x`
+pub(crate) fn sqrt(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    {
+        libm::sqrtf(x)
+    }
+    #[cfg(not(feature = "libm"))]
+    {
+        F32Ext::sqrt(x)
+    }
+}
+
+/// Euclidean norm of a three-dimensional vector
+pub(crate) fn norm(x: f32, y: f32, z: f32) -> f32 {
+    sqrt(x * x + y * y + z * z)
+}
+
+/// Round `x` to the nearest integer, with halfway cases away from zero
+pub(crate) fn round(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    {
+        libm::roundf(x)
+    }
+    #[cfg(not(feature = "libm"))]
+    {
+        F32Ext::round(x)
+    }
+}
+
+/// Four-quadrant arctangent of `y / x`, in radians
+pub(crate) fn atan2(y: f32, x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    {
+        libm::atan2f(y, x)
+    }
+    #[cfg(not(feature = "libm"))]
+    {
+        F32Ext::atan2(y, x)
+    }
+}
+
+/// Sine of `x`, in radians
+#[cfg(feature = "mock")]
+pub(crate) fn sin(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    {
+        libm::sinf(x)
+    }
+    #[cfg(not(feature = "libm"))]
+    {
+        F32Ext::sin(x)
+    }
+}
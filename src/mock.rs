@@ -0,0 +1,227 @@
+//! A simulated device for developing and testing without hardware
+//!
+//! [`MockSensor`] provides the same read methods as [`crate::Icm42670`], and
+//! implements the same [`Accelerometer`] and [`RawAccelerometer`] traits, but
+//! returns programmable canned data instead of communicating with a device.
+
+use core::{convert::Infallible, f32::consts::PI};
+
+use accelerometer::{
+    error::Error as AccelerometerError,
+    vector::{F32x3, I16x3},
+    Accelerometer,
+    RawAccelerometer,
+};
+
+use crate::{math, AccelRange, Error, GyroRange, Hz, Sample};
+
+/// A programmable source of canned sensor data
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Signal {
+    /// The same value for every sample
+    Constant(F32x3),
+    /// A sine wave on each axis, `offset + amplitude * sin(2π * frequency * t)`
+    Sine {
+        /// Peak deviation from the offset of each axis
+        amplitude: F32x3,
+        /// Centre value of each axis
+        offset: F32x3,
+        /// Frequency of the wave
+        frequency: Hz,
+    },
+}
+
+impl Signal {
+    /// Value of the signal `t` seconds after the first sample
+    fn at(&self, t: f32) -> F32x3 {
+        match *self {
+            Signal::Constant(value) => value,
+            Signal::Sine {
+                amplitude,
+                offset,
+                frequency,
+            } => {
+                let s = math::sin(2.0 * PI * frequency.0 * t);
+
+                F32x3::new(
+                    offset.x + amplitude.x * s,
+                    offset.y + amplitude.y * s,
+                    offset.z + amplitude.z * s,
+                )
+            }
+        }
+    }
+}
+
+/// Simulated ICM-42670 which returns canned data
+///
+/// Each read of a sensor returns its next sample, as if reading a device
+/// which produces new data at the configured sample rate. The accelerometer
+/// and gyroscope advance independently, so reading both, e.g. using
+/// [`MockSensor::read_sample`], keeps them in step.
+///
+/// Reads never fail, but return the same [`Error`] type as the real driver so
+/// that code generic over either can be used unchanged.
+#[derive(Clone, Copy, Debug)]
+pub struct MockSensor {
+    accel: Signal,
+    gyro: Signal,
+    temp: f32,
+    sample_rate: Hz,
+    accel_range: AccelRange,
+    gyro_range: GyroRange,
+    accel_index: u32,
+    gyro_index: u32,
+}
+
+impl Default for MockSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockSensor {
+    /// A simulated device lying flat and at rest at 25°C, producing
+    /// samples at 100Hz
+    pub fn new() -> Self {
+        Self {
+            accel: Signal::Constant(F32x3::new(0.0, 0.0, 1.0)),
+            gyro: Signal::Constant(F32x3::new(0.0, 0.0, 0.0)),
+            temp: 25.0,
+            sample_rate: Hz(100.0),
+            accel_range: AccelRange::default(),
+            gyro_range: GyroRange::default(),
+            accel_index: 0,
+            gyro_index: 0,
+        }
+    }
+
+    /// Use `signal`, in g, for the accelerometer data
+    pub fn with_accel(mut self, signal: Signal) -> Self {
+        self.accel = signal;
+        self
+    }
+
+    /// Use `signal`, in deg/sec, for the gyroscope data
+    pub fn with_gyro(mut self, signal: Signal) -> Self {
+        self.gyro = signal;
+        self
+    }
+
+    /// Use a constant temperature, in degrees centigrade
+    pub fn with_temperature(mut self, temp: f32) -> Self {
+        self.temp = temp;
+        self
+    }
+
+    /// Produce samples at `rate` rather than 100Hz
+    pub fn with_sample_rate(mut self, rate: Hz) -> Self {
+        self.sample_rate = rate;
+        self
+    }
+
+    /// Restart both signals from their first sample
+    pub fn reset(&mut self) {
+        self.accel_index = 0;
+        self.gyro_index = 0;
+    }
+
+    /// Return the normalized gyro data for each of the three axes
+    pub fn gyro_norm(&mut self) -> Result<F32x3, Error<Infallible>> {
+        let t = self.gyro_index as f32 / self.sample_rate.0;
+        self.gyro_index = self.gyro_index.wrapping_add(1);
+
+        Ok(self.gyro.at(t))
+    }
+
+    /// Return the raw gyro data for each of the three axes
+    ///
+    /// Values beyond the configured range saturate, as they would on the
+    /// device.
+    pub fn gyro_raw(&mut self) -> Result<I16x3, Error<Infallible>> {
+        let norm = self.gyro_norm()?;
+
+        Ok(to_raw(norm, self.gyro_range.scale_factor()))
+    }
+
+    /// Return the temperature in degrees centigrade
+    pub fn temperature(&mut self) -> Result<f32, Error<Infallible>> {
+        Ok(self.temp)
+    }
+
+    /// Return the raw temperature data
+    pub fn temperature_raw(&mut self) -> Result<i16, Error<Infallible>> {
+        let raw = math::round((self.temp - 25.0) * 128.0).clamp(i16::MIN as f32, i16::MAX as f32);
+
+        Ok(raw as i16)
+    }
+
+    /// Return the next sample of every sensor
+    pub fn read_sample(&mut self) -> Result<Sample, Error<Infallible>> {
+        let accel = self.next_accel();
+        let gyro = self.gyro_norm()?;
+        let temp = self.temperature()?;
+
+        Ok(Sample { accel, gyro, temp })
+    }
+
+    /// Return the simulated accelerometer range
+    pub fn accel_range(&mut self) -> Result<AccelRange, Error<Infallible>> {
+        Ok(self.accel_range)
+    }
+
+    /// Set the range used to produce the raw accelerometer data
+    pub fn set_accel_range(&mut self, range: AccelRange) -> Result<(), Error<Infallible>> {
+        self.accel_range = range;
+
+        Ok(())
+    }
+
+    /// Return the simulated gyroscope range
+    pub fn gyro_range(&mut self) -> Result<GyroRange, Error<Infallible>> {
+        Ok(self.gyro_range)
+    }
+
+    /// Set the range used to produce the raw gyro data
+    pub fn set_gyro_range(&mut self, range: GyroRange) -> Result<(), Error<Infallible>> {
+        self.gyro_range = range;
+
+        Ok(())
+    }
+
+    fn next_accel(&mut self) -> F32x3 {
+        let t = self.accel_index as f32 / self.sample_rate.0;
+        self.accel_index = self.accel_index.wrapping_add(1);
+
+        self.accel.at(t)
+    }
+}
+
+impl Accelerometer for MockSensor {
+    type Error = Error<Infallible>;
+
+    fn accel_norm(&mut self) -> Result<F32x3, AccelerometerError<Self::Error>> {
+        Ok(self.next_accel())
+    }
+
+    fn sample_rate(&mut self) -> Result<f32, AccelerometerError<Self::Error>> {
+        Ok(self.sample_rate.0)
+    }
+}
+
+impl RawAccelerometer<I16x3> for MockSensor {
+    type Error = Error<Infallible>;
+
+    fn accel_raw(&mut self) -> Result<I16x3, AccelerometerError<Self::Error>> {
+        let norm = self.next_accel();
+
+        Ok(to_raw(norm, self.accel_range.scale_factor()))
+    }
+}
+
+/// Convert a normalized reading back to saturated raw counts
+fn to_raw(norm: F32x3, scale: f32) -> I16x3 {
+    let raw = |v: f32| math::round(v * scale).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+
+    I16x3::new(raw(norm.x), raw(norm.y), raw(norm.z))
+}
@@ -4,10 +4,14 @@
 // All reads and writes actually go through User Bank 0, and the remaining banks
 // are accessed via this bank; as such, User Bank 0 has been omitted, given that
 // we are not actually able to "select" it.
-#[derive(Debug, Clone, Copy)]
-pub(crate) enum RegisterBank {
+/// A bank of registers which is accessed indirectly through User Bank 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegisterBank {
+    /// The MREG1 bank
     MReg1,
+    /// The MREG2 bank
     MReg2,
+    /// The MREG3 bank
     MReg3,
 }
 
@@ -30,6 +34,19 @@ pub(crate) trait Register {
     fn read_only(&self) -> bool;
 }
 
+/// A register given only by its address, as used for raw register access
+///
+/// Nothing is known about the register, so it is never treated as read-only.
+impl Register for u8 {
+    fn addr(&self) -> u8 {
+        *self
+    }
+
+    fn read_only(&self) -> bool {
+        false
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum Bank0 {
     MCLK_RDY = 0x00,
@@ -152,6 +169,7 @@ pub(crate) enum Mreg1 {
     SENSOR_CONFIG3 = 0x06,
     ST_CONFIG = 0x13,
     SELFTEST = 0x14,
+    INTF_CONFIG5 = 0x21,
     INTF_CONFIG6 = 0x23,
     INTF_CONFIG10 = 0x25,
     INTF_CONFIG7 = 0x28,
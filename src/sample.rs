@@ -0,0 +1,154 @@
+use core::f32::consts::PI;
+
+use accelerometer::vector::F32x3;
+
+use crate::{math, FifoRecord};
+
+/// A single scaled reading of every sensor on the device
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Acceleration of each axis, in g
+    pub accel: F32x3,
+    /// Angular rate of each axis, in deg/sec
+    pub gyro: F32x3,
+    /// Temperature, in degrees centigrade
+    pub temp: f32,
+}
+
+/// A single scaled reading of every sensor, as plain arrays, with the time
+/// at which it was taken if known
+///
+/// Only FIFO packets carry a timestamp, so this is `None` for readings of
+/// the data registers, such as those returned by [`Icm42670::read_6dof`].
+///
+/// [`Icm42670::read_6dof`]: crate::Icm42670::read_6dof
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Sample6Dof {
+    /// Acceleration of the X, Y, and Z axes, in g
+    pub accel_g: [f32; 3],
+    /// Angular rate of the X, Y, and Z axes, in deg/sec
+    pub gyro_dps: [f32; 3],
+    /// Temperature, in degrees centigrade
+    pub temp_c: f32,
+    /// Timestamp in raw ticks; see [`FifoRecord::timestamp`]
+    pub timestamp: Option<u16>,
+}
+
+impl From<Sample> for Sample6Dof {
+    fn from(sample: Sample) -> Self {
+        Self {
+            accel_g: [sample.accel.x, sample.accel.y, sample.accel.z],
+            gyro_dps: [sample.gyro.x, sample.gyro.y, sample.gyro.z],
+            temp_c: sample.temp,
+            timestamp: None,
+        }
+    }
+}
+
+impl From<FifoRecord> for Sample6Dof {
+    /// A sensor which the packet has no data for is reported as zero, as by
+    /// [`parse_fifo_into`](crate::parse_fifo_into).
+    fn from(record: FifoRecord) -> Self {
+        let array = |v: Option<F32x3>| v.map_or([0.0; 3], |v| [v.x, v.y, v.z]);
+
+        Self {
+            accel_g: array(record.accel),
+            gyro_dps: array(record.gyro),
+            temp_c: record.temp,
+            timestamp: record.timestamp,
+        }
+    }
+}
+
+/// Tilt of the device relative to gravity
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Inclination {
+    /// Rotation about the Y axis, in degrees, positive when the X axis
+    /// points upwards, in the range `-90.0..=90.0`
+    pub pitch_deg: f32,
+    /// Rotation about the X axis, in degrees, positive when the Y axis points
+    /// upwards, in the range `-180.0..=180.0`
+    pub roll_deg: f32,
+}
+
+impl Inclination {
+    /// Compute the tilt from an acceleration vector, in g, which is assumed to
+    /// measure only gravity
+    pub fn from_accel(accel: F32x3) -> Self {
+        let to_deg = 180.0 / PI;
+        let pitch = math::atan2(accel.x, math::sqrt(accel.y * accel.y + accel.z * accel.z));
+        let roll = math::atan2(accel.y, accel.z);
+
+        Self {
+            pitch_deg: pitch * to_deg,
+            roll_deg: roll * to_deg,
+        }
+    }
+}
+
+impl Sample {
+    /// Euclidean norm of the acceleration vector, in g
+    pub fn accel_magnitude(&self) -> f32 {
+        math::norm(self.accel.x, self.accel.y, self.accel.z)
+    }
+
+    /// Euclidean norm of the angular rate vector, in deg/sec
+    pub fn gyro_magnitude(&self) -> f32 {
+        math::norm(self.gyro.x, self.gyro.y, self.gyro.z)
+    }
+
+    /// The values as `[ax, ay, az, gx, gy, gz, temp]`
+    pub fn to_array(&self) -> [f32; 7] {
+        [
+            self.accel.x,
+            self.accel.y,
+            self.accel.z,
+            self.gyro.x,
+            self.gyro.y,
+            self.gyro.z,
+            self.temp,
+        ]
+    }
+
+    /// Is the device at rest?
+    ///
+    /// A device at rest measures only gravity, so the acceleration magnitude
+    /// must be within `accel_threshold` g of 1g and the angular rate
+    /// magnitude must be below `gyro_threshold` deg/sec.
+    pub fn is_stationary(&self, accel_threshold: f32, gyro_threshold: f32) -> bool {
+        let accel_dev = self.accel_magnitude() - 1.0;
+
+        accel_dev < accel_threshold
+            && accel_dev > -accel_threshold
+            && self.gyro_magnitude() < gyro_threshold
+    }
+
+    /// Column names matching the values written by [`Sample::write_csv`]
+    #[cfg(feature = "std")]
+    pub const CSV_HEADER: &'static str = "ax,ay,az,gx,gy,gz,temp";
+
+    /// Write [`Sample::CSV_HEADER`] to `w`, followed by a newline
+    #[cfg(feature = "std")]
+    pub fn write_csv_header(w: &mut impl std::io::Write) -> std::io::Result<()> {
+        writeln!(w, "{}", Self::CSV_HEADER)
+    }
+
+    /// Write the sample to `w` as a single line of comma-separated values
+    ///
+    /// Accelerations are written with 6 decimal places, angular rates with 3,
+    /// and the temperature with 2, so that every line has the same precision.
+    #[cfg(feature = "std")]
+    pub fn write_csv(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        writeln!(
+            w,
+            "{:.6},{:.6},{:.6},{:.3},{:.3},{:.3},{:.2}",
+            self.accel.x,
+            self.accel.y,
+            self.accel.z,
+            self.gyro.x,
+            self.gyro.y,
+            self.gyro.z,
+            self.temp,
+        )
+    }
+}
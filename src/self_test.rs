@@ -0,0 +1,246 @@
+use core::fmt::Debug;
+
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::{
+    delay_ms,
+    register::{Bank0, Mreg1, Mreg2, RegisterBank},
+    AccelOdr,
+    Config,
+    Error,
+    GyroOdr,
+    Icm42670,
+    Interface,
+    PowerMode,
+};
+
+/// Outcome of the factory self-test
+///
+/// The device applies an electrostatic stimulus to each axis and compares
+/// the response with the reference values stored in its OTP memory during
+/// production, reporting whether each axis passed. The device does not report
+/// the responses it measured, so the driver measures them itself beforehand,
+/// as the change in each axis's output when the stimulus is applied.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SelfTestResult {
+    /// Did each of the X, Y, and Z axes of the accelerometer pass?
+    pub accel_pass: [bool; 3],
+    /// Did each of the X, Y, and Z axes of the gyroscope pass?
+    pub gyro_pass: [bool; 3],
+    /// Did the accelerometer pass as a whole?
+    pub accel_ok: bool,
+    /// Did the gyroscope pass as a whole?
+    pub gyro_ok: bool,
+    /// Was the test interrupted before it completed?
+    pub incomplete: bool,
+    /// Response of the X, Y, and Z axes of the accelerometer to the
+    /// stimulus, in counts at the configured full-scale range
+    pub accel_delta: [i16; 3],
+    /// Response of the X, Y, and Z axes of the gyroscope to the stimulus, in
+    /// counts at the configured full-scale range
+    pub gyro_delta: [i16; 3],
+}
+
+impl SelfTestResult {
+    /// Did both sensors pass a complete test?
+    pub fn passed(&self) -> bool {
+        self.accel_ok && self.gyro_ok && !self.incomplete
+    }
+
+    /// Decode the `ST_STATUS1` and `ST_STATUS2` registers
+    fn from_registers(status1: u8, status2: u8) -> Self {
+        let bit = |reg: u8, n: u8| reg & (1 << n) != 0;
+
+        // The per-axis results occupy bits 3:1 of both registers
+        Self {
+            accel_pass: [bit(status1, 1), bit(status1, 2), bit(status1, 3)],
+            gyro_pass: [bit(status2, 1), bit(status2, 2), bit(status2, 3)],
+            accel_ok: bit(status1, 5),
+            gyro_ok: bit(status2, 5),
+            incomplete: bit(status2, 6),
+            ..Self::default()
+        }
+    }
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Run the factory self-test of both sensors
+    ///
+    /// The device must be stationary for the duration, which is up to one
+    /// second. The test is run by the DMP, so any APEX features must be
+    /// re-initialized with [`Icm42670::init_dmp`] afterwards, and the FIFO is
+    /// flushed, as the test may have written to it. Both sensors are run in
+    /// low-noise mode at 800Hz while the response to the stimulus is
+    /// measured, and the previous configuration, including the power mode and
+    /// ranges, is restored afterwards whether or not the test completed.
+    pub fn self_test(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<SelfTestResult, Error<E>> {
+        let config = self.config;
+
+        let result = self
+            .measure_self_test_response(delay)
+            .and_then(|(accel, gyro)| {
+                Ok(SelfTestResult {
+                    accel_delta: accel,
+                    gyro_delta: gyro,
+                    ..self.run_self_test(delay)?
+                })
+            });
+        self.dmp_initialized = false;
+        self.apply_config(config)?;
+
+        result
+    }
+
+    /// Measure the response of each axis to the stimulus, as the difference
+    /// between its mean output with the stimulus applied and without.
+    fn measure_self_test_response(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<([i16; 3], [i16; 3]), Error<E>> {
+        self.apply_config(Config {
+            accel_odr: AccelOdr::Hz800,
+            gyro_odr: GyroOdr::Hz800,
+            power_mode: PowerMode::SixAxisLowNoise,
+            ..self.config
+        })?;
+        // Allow the gyroscope, the slower of the sensors, to start
+        delay_ms(delay, 45);
+
+        let (accel_off, gyro_off) = self.mean_outputs(delay)?;
+
+        // The per-axis enables for the gyroscope and accelerometer occupy bits
+        // 5:3 and 2:0 in the register. Without `GYRO_ST_EN` or `ACCEL_ST_EN`
+        // they only apply the stimulus, rather than starting the test.
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::SELFTEST, 0b0011_1111)?;
+        delay_ms(delay, 20);
+        let result = self.mean_outputs(delay);
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::SELFTEST, 0x00)?;
+        let (accel_on, gyro_on) = result?;
+
+        let delta = |on: [i32; 3], off: [i32; 3]| {
+            let axis = |i: usize| (on[i] - off[i]).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+
+            [axis(0), axis(1), axis(2)]
+        };
+
+        Ok((delta(accel_on, accel_off), delta(gyro_on, gyro_off)))
+    }
+
+    /// Average the raw output of each axis of both sensors over several
+    /// samples.
+    fn mean_outputs(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<([i32; 3], [i32; 3]), Error<E>> {
+        const SAMPLES: i32 = 16;
+
+        let mut accel = [0i32; 3];
+        let mut gyro = [0i32; 3];
+        for _ in 0..SAMPLES {
+            self.wait_data_ready_for(delay, 10_000)?;
+            let a = self.read_vector(&Bank0::ACCEL_DATA_X1)?;
+            let g = self.read_vector(&Bank0::GYRO_DATA_X1)?;
+
+            for (sum, value) in accel.iter_mut().zip([a.x, a.y, a.z]) {
+                *sum += value as i32;
+            }
+            for (sum, value) in gyro.iter_mut().zip([g.x, g.y, g.z]) {
+                *sum += value as i32;
+            }
+        }
+
+        Ok((
+            accel.map(|sum| sum / SAMPLES),
+            gyro.map(|sum| sum / SAMPLES),
+        ))
+    }
+
+    /// Run the self-test, leaving the sensors off afterwards.
+    fn run_self_test(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<SelfTestResult, Error<E>> {
+        const INTERVAL_US: u8 = 250;
+
+        // The MREG banks require the internal clock, which is kept running in
+        // idle mode while the sensors are off
+        self.set_power_mode(PowerMode::Idle)?;
+        delay.delay_us(200);
+
+        // Clear the DMP's memory, which the test uses. `DMP_MEM_RESET_EN`
+        // occupies bit 0 in the register, and is cleared by the device once
+        // the reset has completed.
+        self.update_reg(&Bank0::APEX_CONFIG0, 0b0000_0001, 0b0000_0001)?;
+        delay_ms(delay, 1);
+        self.poll_until(delay, "DMP memory reset", 500, 100, |me| {
+            Ok(me.read_reg(&Bank0::APEX_CONFIG0)? & 0b0000_0001 == 0)
+        })?;
+
+        self.load_self_test_reference(delay)?;
+
+        // `ST_NUMBER_SAMPLE` occupies bit 6, `ACCEL_ST_LIM` bits 5:3, and
+        // `GYRO_ST_LIM` bits 2:0 in the register. Average 16 samples, and
+        // allow each response to differ from its reference by up to 50%.
+        let st_config = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ST_CONFIG)?;
+        let st_config = (st_config & 0b1000_0000) | 0b0011_1111;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::ST_CONFIG, st_config)?;
+
+        // `GYRO_ST_EN` occupies bit 7, `ACCEL_ST_EN` bit 6, and the per-axis
+        // enables for the gyroscope and accelerometer bits 5:3 and 2:0 in the
+        // register
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::SELFTEST, 0xFF)?;
+
+        // `ST_INT` occupies bit 7 in the register. Allow up to 1s for the test
+        // to complete.
+        let completed = self.poll_until(delay, "self-test", 4_000, INTERVAL_US, |me| {
+            Ok(me.read_status_reg(Bank0::INT_STATUS)? & 0b1000_0000 != 0)
+        });
+
+        let status1 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ST_STATUS1)?;
+        let status2 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ST_STATUS2)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::SELFTEST, 0x00)?;
+
+        // The test may have written to the FIFO. `FIFO_FLUSH` occupies bit 2
+        // in the register.
+        self.update_reg(&Bank0::SIGNAL_PATH_RESET, 0b0000_0100, 0b0000_0100)?;
+
+        completed?;
+
+        Ok(SelfTestResult::from_registers(status1, status2))
+    }
+
+    /// Copy the reference responses from the OTP memory into the DMP's
+    /// memory, where the test compares them with the measured responses.
+    fn load_self_test_reference(&mut self, delay: &mut dyn DelayUs<u8>) -> Result<(), Error<E>> {
+        // `OTP_COPY_MODE` occupies bits 3:2 in the register, where 0b11
+        // selects the self-test data
+        let otp_config = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OTP_CONFIG)?;
+        let otp_config = otp_config | 0b0000_1100;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OTP_CONFIG, otp_config)?;
+
+        // `OTP_RELOAD` occupies bit 3 and `OTP_PWR_DOWN` bit 1 in the
+        // register. The OTP memory is powered up, given 100µs to start, and
+        // then reloaded, which takes 20µs, before it is powered down again.
+        let ctrl7 = self.read_mreg(delay, RegisterBank::MReg2, &Mreg2::OTP_CTRL7)?;
+        let ctrl7 = ctrl7 & !0b0000_0010;
+        self.write_mreg(delay, RegisterBank::MReg2, &Mreg2::OTP_CTRL7, ctrl7)?;
+        delay.delay_us(100);
+
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg2,
+            &Mreg2::OTP_CTRL7,
+            ctrl7 | 0b0000_1000,
+        )?;
+        delay.delay_us(20);
+
+        let ctrl7 = self.read_mreg(delay, RegisterBank::MReg2, &Mreg2::OTP_CTRL7)?;
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg2,
+            &Mreg2::OTP_CTRL7,
+            ctrl7 | 0b0000_0010,
+        )
+    }
+}
@@ -0,0 +1,40 @@
+//! Scalar physical quantities, tagged with their units
+//!
+//! The vector-valued reads return [`accelerometer::vector::F32x3`], as
+//! required by the [`accelerometer::Accelerometer`] trait, so these are used
+//! where the driver returns a single quantity.
+
+/// Standard gravity, in m/s²
+pub(crate) const STANDARD_GRAVITY: f32 = 9.80665;
+
+/// An acceleration, in g
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Acceleration(pub f32);
+
+impl Acceleration {
+    /// The acceleration in g
+    pub fn as_g(self) -> f32 {
+        self.0
+    }
+
+    /// The acceleration in m/s², taking 1g to be standard gravity
+    pub fn as_mps2(self) -> f32 {
+        self.0 * STANDARD_GRAVITY
+    }
+}
+
+/// An angular velocity, in deg/sec
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct AngularVelocity(pub f32);
+
+impl AngularVelocity {
+    /// The angular velocity in deg/sec
+    pub fn as_dps(self) -> f32 {
+        self.0
+    }
+
+    /// The angular velocity in rad/sec
+    pub fn as_rad_per_sec(self) -> f32 {
+        self.0.to_radians()
+    }
+}
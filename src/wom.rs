@@ -0,0 +1,173 @@
+use core::fmt::Debug;
+
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::{
+    register::{Bank0, Mreg1, RegisterBank},
+    Error,
+    Icm42670,
+    IntPin,
+    Interface,
+    PowerMode,
+};
+
+/// How the axes are combined to trigger a wake on motion interrupt
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WomMode {
+    /// Trigger when any axis exceeds its threshold
+    AnyAxis,
+    /// Trigger only when every axis exceeds its threshold
+    AllAxes,
+}
+
+impl Default for WomMode {
+    fn default() -> Self {
+        WomMode::AnyAxis
+    }
+}
+
+/// The sample with which each new accelerometer sample is compared
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WomCompare {
+    /// Compare with the previous sample, detecting changes in acceleration
+    Previous,
+    /// Compare with the first sample taken after wake on motion was enabled,
+    /// detecting changes in orientation
+    Initial,
+}
+
+impl Default for WomCompare {
+    fn default() -> Self {
+        WomCompare::Previous
+    }
+}
+
+/// Configuration of the wake on motion feature
+///
+/// Wake on motion compares each accelerometer sample against a reference
+/// sample, and raises an interrupt when the difference exceeds a threshold.
+/// It runs in every power mode in which the accelerometer is enabled, but is
+/// usually combined with [`crate::PowerMode::AccelLowPower`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WomConfig {
+    /// Threshold of the X, Y, and Z axes, in units of 1/256g (about 3.9mg)
+    pub threshold: [u8; 3],
+    /// How the axes are combined
+    pub mode: WomMode,
+    /// The sample with which each new sample is compared
+    pub compare: WomCompare,
+    /// Number of consecutive samples which must exceed the threshold before
+    /// the interrupt is raised, from 1 to 4
+    pub samples: u8,
+}
+
+impl Default for WomConfig {
+    /// A threshold of 250mg on every axis, triggered by a single sample of
+    /// any axis compared with the previous sample
+    fn default() -> Self {
+        Self {
+            threshold: [64; 3],
+            mode: WomMode::default(),
+            compare: WomCompare::default(),
+            samples: 1,
+        }
+    }
+}
+
+impl<DI, E> Icm42670<DI>
+where
+    DI: Interface<Error = E>,
+    E: Debug,
+{
+    /// Configure and enable wake on motion
+    ///
+    /// The thresholds are held in the MREG1 bank, so this cannot be called
+    /// in [`crate::PowerMode::Sleep`]. Route the wake on motion conditions to
+    /// an interrupt pin with [`Icm42670::set_int_sources`], and read which
+    /// axes triggered it with [`Icm42670::wom_triggered`].
+    pub fn enable_wom(
+        &mut self,
+        config: WomConfig,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        let [x, y, z] = config.threshold;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_X_THR, x)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_Y_THR, y)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_Z_THR, z)?;
+
+        // `WOM_INT_DUR` occupies bits 4:3 in the register
+        // `WOM_INT_MODE` occupies bit 2 in the register
+        // `WOM_MODE` occupies bit 1 in the register
+        // `WOM_EN` occupies bit 0 in the register
+        let mut value = (config.samples.clamp(1, 4) - 1) << 3 | 0b0000_0001;
+        if config.mode == WomMode::AllAxes {
+            value |= 0b0000_0100;
+        }
+        if config.compare == WomCompare::Previous {
+            value |= 0b0000_0010;
+        }
+
+        self.write_reg(&Bank0::WOM_CONFIG, value)
+    }
+
+    /// Enable wake on motion, route its conditions to `pin`, and put the
+    /// accelerometer into [`PowerMode::AccelLowPower`]
+    ///
+    /// This is the usual setup for using the device as a low-power motion
+    /// trigger. Any other conditions already routed to `pin` remain routed.
+    /// The configured accelerometer output data rate must be supported in
+    /// [`PowerMode::AccelLowPower`]; it sets the rate at which motion is
+    /// checked.
+    ///
+    /// The thresholds are written to the MREG1 bank before the power mode is
+    /// changed, as the bank cannot be accessed once the accelerometer is
+    /// duty-cycled by the wake-up oscillator, so this cannot be called in
+    /// [`PowerMode::Sleep`].
+    pub fn configure_wom(
+        &mut self,
+        config: WomConfig,
+        pin: IntPin,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(), Error<E>> {
+        self.enable_wom(config, delay)?;
+        self.route_wom(pin)?;
+
+        self.set_power_mode_staged(PowerMode::AccelLowPower, delay)
+    }
+
+    /// Disable wake on motion, leaving its configuration unchanged
+    pub fn disable_wom(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(&Bank0::WOM_CONFIG, 0, 0b0000_0001)
+    }
+
+    /// Read back the configuration of wake on motion, and whether it is
+    /// enabled
+    ///
+    /// The MREG1 bank cannot be read in [`crate::PowerMode::Sleep`].
+    pub fn read_wom_config(
+        &mut self,
+        delay: &mut dyn DelayUs<u8>,
+    ) -> Result<(WomConfig, bool), Error<E>> {
+        let x = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_X_THR)?;
+        let y = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_Y_THR)?;
+        let z = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_Z_THR)?;
+        let value = self.read_reg(&Bank0::WOM_CONFIG)?;
+
+        let config = WomConfig {
+            threshold: [x, y, z],
+            mode: if value & 0b0000_0100 != 0 {
+                WomMode::AllAxes
+            } else {
+                WomMode::AnyAxis
+            },
+            compare: if value & 0b0000_0010 != 0 {
+                WomCompare::Previous
+            } else {
+                WomCompare::Initial
+            },
+            samples: ((value >> 3) & 0b11) + 1,
+        };
+
+        Ok((config, value & 0b0000_0001 != 0))
+    }
+}
@@ -0,0 +1,279 @@
+use icm42670::{
+    AccelAvg,
+    AccelBw,
+    AccelOdr,
+    AccelRange,
+    Config,
+    GyroBw,
+    GyroOdr,
+    GyroRange,
+    PowerMode,
+    SensorError,
+    SmdSensitivity,
+};
+
+#[test]
+fn power_mode_round_trip() {
+    let modes = [
+        PowerMode::Sleep,
+        PowerMode::Standby,
+        PowerMode::AccelLowPower,
+        PowerMode::AccelLowNoise,
+        PowerMode::GyroLowNoise,
+        PowerMode::SixAxisLowNoise,
+        PowerMode::Idle,
+    ];
+
+    for mode in modes {
+        assert_eq!(PowerMode::try_from(mode as u8).unwrap(), mode);
+    }
+}
+
+#[test]
+fn smd_sensitivity_round_trip() {
+    let levels = [
+        SmdSensitivity::Level0,
+        SmdSensitivity::Level1,
+        SmdSensitivity::Level2,
+        SmdSensitivity::Level3,
+        SmdSensitivity::Level4,
+    ];
+
+    for level in levels {
+        assert_eq!(SmdSensitivity::try_from(level as u8).unwrap(), level);
+    }
+    assert!(matches!(
+        SmdSensitivity::try_from(0b101),
+        Err(SensorError::InvalidDiscriminant)
+    ));
+}
+
+#[test]
+fn accel_avg_round_trip() {
+    let avgs = [
+        AccelAvg::X2,
+        AccelAvg::X4,
+        AccelAvg::X8,
+        AccelAvg::X16,
+        AccelAvg::X32,
+        AccelAvg::X64,
+    ];
+
+    for avg in avgs {
+        assert_eq!(AccelAvg::try_from(avg as u8).unwrap(), avg);
+    }
+    assert_eq!(AccelAvg::X64.count(), 64);
+    assert!(matches!(
+        AccelAvg::try_from(0b110),
+        Err(SensorError::InvalidDiscriminant)
+    ));
+}
+
+#[test]
+fn config_validate() {
+    let config = Config {
+        power_mode: PowerMode::SixAxisLowNoise,
+        ..Config::default()
+    };
+    assert!(config.validate().is_ok());
+
+    let config = Config {
+        accel_odr: AccelOdr::Hz6_25,
+        accel_bw: AccelBw::Hz10000,
+        ..config
+    };
+    assert!(matches!(
+        config.validate(),
+        Err(SensorError::InvalidConfig { .. })
+    ));
+    assert!(Config {
+        power_mode: PowerMode::AccelLowPower,
+        ..config
+    }
+    .validate()
+    .is_ok());
+
+    let config = Config {
+        gyro_odr: GyroOdr::Hz100,
+        ..Config::default()
+    };
+    assert!(Config {
+        power_mode: PowerMode::AccelLowNoise,
+        ..config
+    }
+    .validate()
+    .is_ok());
+    assert!(matches!(
+        Config {
+            power_mode: PowerMode::GyroLowNoise,
+            ..config
+        }
+        .validate(),
+        Err(SensorError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn odr_for_max_period() {
+    assert_eq!(AccelOdr::for_max_period_ms(5.0), Some(AccelOdr::Hz200));
+    assert_eq!(AccelOdr::for_max_period_ms(4.9), Some(AccelOdr::Hz400));
+    assert_eq!(
+        AccelOdr::for_max_period_ms(1000.0),
+        Some(AccelOdr::Hz1_5625)
+    );
+    assert_eq!(AccelOdr::for_max_period_ms(0.5), None);
+    assert_eq!(GyroOdr::for_max_period_ms(1000.0), Some(GyroOdr::Hz12_5));
+}
+
+#[test]
+fn raw_conversion() {
+    assert_eq!(AccelRange::G16.to_g(2048), 1.0);
+    assert_eq!(AccelRange::G2.to_g(-16_384), -1.0);
+    assert_eq!(GyroRange::Deg250.to_dps(131), 1.0);
+    assert_eq!(GyroRange::Deg2000.to_dps(-164), -10.0);
+
+    // The most negative reading is the full-scale endpoint of each range
+    assert_eq!(AccelRange::G16.to_g(i16::MIN), -16.0);
+    assert_eq!(AccelRange::G2.to_g(i16::MIN), -2.0);
+    let error = GyroRange::Deg2000.to_dps(i16::MIN) + 2000.0;
+    assert!(error < 2.5 && error > -2.5);
+
+    let rads = GyroRange::Deg250.to_rads(131 * 90);
+    let error = rads - core::f32::consts::FRAC_PI_2;
+    assert!(error < 1e-4 && error > -1e-4);
+}
+
+#[test]
+fn supported_odrs() {
+    let low_noise = PowerMode::AccelLowNoise.supported_accel_odrs();
+    assert!(low_noise.contains(&AccelOdr::Hz1600));
+    assert!(!low_noise.contains(&AccelOdr::Hz6_25));
+
+    let low_power = PowerMode::AccelLowPower.supported_accel_odrs();
+    assert!(low_power.contains(&AccelOdr::Hz1_5625));
+    assert!(!low_power.contains(&AccelOdr::Hz800));
+
+    assert!(PowerMode::GyroLowNoise.supported_accel_odrs().is_empty());
+    assert!(PowerMode::Standby.supported_gyro_odrs().is_empty());
+    assert!(PowerMode::SixAxisLowNoise
+        .supported_gyro_odrs()
+        .contains(&GyroOdr::Hz12_5));
+}
+
+#[test]
+fn aliasing_risk() {
+    let config = Config {
+        power_mode: PowerMode::SixAxisLowNoise,
+        ..Config::default()
+    };
+    assert!(!config.aliasing_risk());
+
+    let config = Config {
+        accel_bw: AccelBw::Hz10000,
+        ..config
+    };
+    assert!(config.aliasing_risk());
+    assert!(!Config {
+        power_mode: PowerMode::GyroLowNoise,
+        ..config
+    }
+    .aliasing_risk());
+}
+
+#[test]
+fn recommended_bw() {
+    let config = Config {
+        accel_odr: AccelOdr::Hz100,
+        gyro_odr: GyroOdr::Hz800,
+        ..Config::default()
+    };
+    assert_eq!(config.recommended_bw(), (AccelBw::Hz34, GyroBw::Hz180));
+
+    let config = Config {
+        power_mode: PowerMode::SixAxisLowNoise,
+        accel_odr: AccelOdr::Hz12_5,
+        ..config
+    };
+    let (accel_bw, gyro_bw) = config.recommended_bw();
+    assert_eq!(accel_bw, AccelBw::Hz10000);
+    assert!(Config {
+        accel_bw,
+        gyro_bw,
+        ..config
+    }
+    .validate()
+    .is_ok());
+}
+
+#[test]
+fn effective_accel_period() {
+    let config = Config {
+        power_mode: PowerMode::AccelLowPower,
+        accel_odr: AccelOdr::Hz1_5625,
+        ..Config::default()
+    };
+    assert_eq!(config.effective_accel_period_us(), Some(640_000));
+
+    let config = Config {
+        power_mode: PowerMode::GyroLowNoise,
+        ..config
+    };
+    assert_eq!(config.effective_accel_period_us(), None);
+}
+
+#[test]
+fn config_builder() {
+    let config = Config::default()
+        .with_accel_range(AccelRange::G4)
+        .with_accel_odr(AccelOdr::Hz400)
+        .with_gyro_odr(GyroOdr::Hz400)
+        .with_power_mode(PowerMode::SixAxisLowNoise);
+
+    assert_eq!(
+        config,
+        Config {
+            accel_range: AccelRange::G4,
+            accel_odr: AccelOdr::Hz400,
+            gyro_odr: GyroOdr::Hz400,
+            power_mode: PowerMode::SixAxisLowNoise,
+            ..Config::default()
+        }
+    );
+    assert!(config.validate().is_ok());
+    assert!(config
+        .with_accel_odr(AccelOdr::Hz1_5625)
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn odr_valid_for_power_mode() {
+    assert!(AccelOdr::Hz1600.is_valid_for(PowerMode::AccelLowNoise));
+    assert!(!AccelOdr::Hz1600.is_valid_for(PowerMode::AccelLowPower));
+    assert!(AccelOdr::Hz6_25.is_valid_for(PowerMode::AccelLowPower));
+    assert!(!AccelOdr::Hz6_25.is_valid_for(PowerMode::SixAxisLowNoise));
+    assert!(AccelOdr::Hz6_25.is_valid_for(PowerMode::Sleep));
+    assert!(GyroOdr::Hz12_5.is_valid_for(PowerMode::GyroLowNoise));
+    assert!(GyroOdr::Hz1600.is_valid_for(PowerMode::Sleep));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_discriminants() {
+    use icm42670::Address;
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    assert_tokens(&Address::Secondary, &[Token::U8(0x69)]);
+    assert_tokens(&AccelRange::G4, &[Token::U8(2)]);
+    assert_tokens(&GyroRange::Deg500, &[Token::U8(2)]);
+    assert_tokens(&PowerMode::SixAxisLowNoise, &[Token::U8(0b01111)]);
+    assert_tokens(&AccelOdr::Hz25, &[Token::U8(0b1011)]);
+    assert_tokens(&GyroOdr::Hz25, &[Token::U8(0b1011)]);
+    assert_tokens(&GyroBw::Hz73, &[Token::U8(0b011)]);
+    assert_tokens(&AccelBw::Hz121, &[Token::U8(0b010)]);
+
+    assert_de_tokens_error::<AccelRange>(
+        &[Token::U8(4)],
+        "invalid value: integer `4`, expected AccelRange",
+    );
+}
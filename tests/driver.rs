@@ -0,0 +1,341 @@
+use std::collections::VecDeque;
+
+use embedded_hal::blocking::delay::DelayUs;
+use icm42670::{
+    AccelAvg,
+    AccelOdr,
+    AccelRange,
+    ByteOrder,
+    Config,
+    Error,
+    FifoConfig,
+    FifoPacketType,
+    FifoReadStatus,
+    FifoRecord,
+    Icm42670,
+    Interface,
+    PowerMode,
+    SensorError,
+};
+
+const MCLK_RDY: u8 = 0x00;
+const INT_STATUS_DRDY: u8 = 0x39;
+const INT_STATUS: u8 = 0x3A;
+const FIFO_COUNTH: u8 = 0x3D;
+const FIFO_COUNTL: u8 = 0x3E;
+const FIFO_DATA: u8 = 0x3F;
+
+/// A register file standing in for the device, with a FIFO behind
+/// `FIFO_DATA` and every bus transaction recorded
+#[derive(Default)]
+struct FakeDevice {
+    regs: Vec<u8>,
+    fifo: VecDeque<u8>,
+    /// Address and length of every read transaction
+    reads: Vec<(u8, usize)>,
+    /// Number of transactions which will fail before the device responds
+    nacks: usize,
+    /// Number of transactions which will succeed before a single one fails
+    nack_after: Option<usize>,
+}
+
+/// The device failed to acknowledge a transaction
+#[derive(Debug)]
+struct Nack;
+
+impl FakeDevice {
+    fn new() -> Self {
+        let mut regs = vec![0; 128];
+        regs[MCLK_RDY as usize] = 0b0000_1000;
+
+        Self {
+            regs,
+            ..Self::default()
+        }
+    }
+
+    fn reg(&mut self, addr: u8) -> u8 {
+        match addr {
+            FIFO_COUNTH => (self.fifo.len() >> 8) as u8,
+            FIFO_COUNTL => self.fifo.len() as u8,
+            _ => self.regs[addr as usize],
+        }
+    }
+
+    fn nack(&mut self) -> Result<(), Nack> {
+        if self.nacks > 0 {
+            self.nacks -= 1;
+            return Err(Nack);
+        }
+
+        match self.nack_after {
+            Some(0) => {
+                self.nack_after = None;
+                Err(Nack)
+            }
+            Some(n) => {
+                self.nack_after = Some(n - 1);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Interface for FakeDevice {
+    type Error = Nack;
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Nack> {
+        self.nack()?;
+        self.reads.push((addr, buffer.len()));
+
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            *byte = if addr == FIFO_DATA {
+                self.fifo.pop_front().unwrap_or(0xFF)
+            } else {
+                self.reg(addr + i as u8)
+            };
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Nack> {
+        self.nack()?;
+        let (&addr, values) = data.split_first().unwrap();
+        let start = addr as usize;
+        self.regs[start..start + values.len()].copy_from_slice(values);
+
+        Ok(())
+    }
+}
+
+struct NoDelay;
+
+impl DelayUs<u8> for NoDelay {
+    fn delay_us(&mut self, _us: u8) {}
+}
+
+#[test]
+fn drain_fifo_whole_packets() {
+    // Two and a half 16 byte packets, after the FIFO has overflowed
+    let mut device = FakeDevice::new();
+    device.fifo.extend([0x68; 40]);
+    device.regs[INT_STATUS as usize] = 0b0000_0010;
+    let mut imu = Icm42670::from_interface(device, Config::default());
+    imu.enable_fifo(FifoConfig::default(), &mut NoDelay)
+        .unwrap();
+
+    let mut buffer = [0u8; 64];
+    let status = imu.drain_fifo_packets(&mut buffer).unwrap();
+
+    assert_eq!(
+        status,
+        FifoReadStatus {
+            bytes_read: 32,
+            overflowed: true,
+        }
+    );
+    assert_eq!(imu.free_interface().fifo.len(), 8);
+}
+
+#[test]
+fn sensor_data_single_transaction() {
+    let config = Config {
+        power_mode: PowerMode::SixAxisLowNoise,
+        ..Config::default()
+    };
+    let mut imu = Icm42670::from_interface(FakeDevice::new(), config);
+
+    imu.gyro_raw().unwrap();
+    imu.read_all().unwrap();
+
+    // GYRO_DATA_X1 through GYRO_DATA_Z0, then TEMP_DATA1 through GYRO_DATA_Z0
+    assert_eq!(imu.free_interface().reads, [(0x11, 6), (0x09, 14)]);
+}
+
+#[test]
+fn poll_only_returns_new_data() {
+    let config = Config {
+        power_mode: PowerMode::SixAxisLowNoise,
+        ..Config::default()
+    };
+    let mut device = FakeDevice::new();
+    // DATA_RDY_INT, and 1g on the accelerometer's Z axis at the default ±16g
+    device.regs[INT_STATUS_DRDY as usize] = 0b0000_0001;
+    device.regs[0x0F..=0x10].copy_from_slice(&2048i16.to_be_bytes());
+    let mut imu = Icm42670::from_interface(device, config);
+
+    let sample = imu.poll().unwrap().unwrap();
+    assert_eq!(sample.accel.z, 1.0);
+
+    let mut device = imu.free_interface();
+    device.regs[INT_STATUS_DRDY as usize] = 0;
+    let mut imu = Icm42670::from_interface(device, config);
+
+    assert_eq!(imu.poll().unwrap(), None);
+}
+
+#[test]
+fn power_mode_read_back() {
+    let mut imu = Icm42670::from_interface(FakeDevice::new(), Config::default());
+
+    for mode in [PowerMode::Idle, PowerMode::AccelLowPower, PowerMode::Sleep] {
+        imu.set_power_mode(mode).unwrap();
+        assert_eq!(imu.power_mode().unwrap(), mode);
+    }
+}
+
+#[test]
+fn bus_retries() {
+    let mut device = FakeDevice::new();
+    device.nacks = 3;
+    let mut imu = Icm42670::from_interface(device, Config::default());
+
+    // The first attempt and a single retry both fail...
+    imu.set_bus_retries(1);
+    assert!(matches!(imu.power_mode(), Err(Error::BusError(Nack))));
+
+    // ...but a second retry gets through
+    imu.set_bus_retries(2);
+    assert_eq!(imu.power_mode().unwrap(), PowerMode::Sleep);
+}
+
+#[test]
+fn checked_config_propagates_bus_errors() {
+    let mut device = FakeDevice::new();
+    // GYRO_CONFIG0 through ACCEL_CONFIG1, then PWR_MGMT0, are read before the
+    // new configuration is written
+    device.nack_after = Some(2);
+    device.regs[0x20..=0x24].copy_from_slice(&[0x66, 0x66, 0x00, 0x37, 0x44]);
+    let mut imu = Icm42670::from_interface(device, Config::default());
+
+    let config = Config {
+        accel_range: AccelRange::G4,
+        ..Config::default()
+    };
+    let result = imu.apply_config_checked(config, &mut NoDelay);
+
+    assert!(matches!(result, Err(Error::BusError(Nack))));
+    assert_eq!(
+        imu.free_interface().regs[0x20..=0x24],
+        [0x66, 0x66, 0x00, 0x37, 0x44]
+    );
+}
+
+#[test]
+fn little_endian_data() {
+    let config = Config {
+        power_mode: PowerMode::SixAxisLowNoise,
+        ..Config::default()
+    };
+    let mut device = FakeDevice::new();
+    device.regs[0x0F..=0x10].copy_from_slice(&2048i16.to_le_bytes());
+    let mut imu = Icm42670::from_interface(device, config);
+
+    imu.set_data_endianness(ByteOrder::LittleEndian).unwrap();
+
+    assert_eq!(imu.read_accel_magnitude().unwrap().as_g(), 1.0);
+    assert_eq!(imu.read_all().unwrap().accel.z, 1.0);
+    assert_eq!(imu.free_interface().regs[0x35] & 0b0011_0000, 0);
+}
+
+#[test]
+fn range_preserves_odr() {
+    let config = Config {
+        power_mode: PowerMode::SixAxisLowNoise,
+        ..Config::default()
+    };
+    let mut imu = Icm42670::from_interface(FakeDevice::new(), config);
+
+    imu.set_accel_odr(AccelOdr::Hz400).unwrap();
+    imu.set_accel_range(AccelRange::G4).unwrap();
+
+    assert_eq!(imu.accel_odr().unwrap(), AccelOdr::Hz400);
+    assert_eq!(imu.accel_range().unwrap(), AccelRange::G4);
+}
+
+#[test]
+fn read_fifo_records_whole_packets() {
+    // One and a half 16 byte packets
+    let mut device = FakeDevice::new();
+    device.fifo.extend([0x68; 24]);
+    let mut imu = Icm42670::from_interface(device, Config::default());
+    imu.enable_fifo(FifoConfig::default(), &mut NoDelay)
+        .unwrap();
+
+    let mut buffer = [0u8; 64];
+    let mut records = [FifoRecord::default(); 4];
+    assert_eq!(imu.read_fifo_records(&mut buffer, &mut records).unwrap(), 1);
+    assert_eq!(imu.read_fifo_records(&mut [], &mut records).unwrap(), 0);
+    assert_eq!(imu.free_interface().fifo.len(), 8);
+}
+
+#[test]
+fn read_fifo_records_leaves_unread_packets() {
+    // Two 16 byte packets, but room for only one record
+    let mut device = FakeDevice::new();
+    device.fifo.extend([0x68; 32]);
+    let mut imu = Icm42670::from_interface(device, Config::default());
+    imu.enable_fifo(FifoConfig::default(), &mut NoDelay)
+        .unwrap();
+
+    let mut buffer = [0u8; 64];
+    let mut records = [FifoRecord::default(); 1];
+    assert_eq!(imu.read_fifo_records(&mut buffer, &mut records).unwrap(), 1);
+    assert_eq!(imu.free_interface().fifo.len(), 16);
+}
+
+#[test]
+fn estimated_current_per_power_mode() {
+    let current = |power_mode: PowerMode| {
+        let config = Config {
+            power_mode,
+            ..Config::default()
+        };
+
+        Icm42670::from_interface(FakeDevice::new(), config).estimated_current_ua()
+    };
+
+    assert_eq!(current(PowerMode::Sleep), 3);
+    assert_eq!(current(PowerMode::Idle), 35);
+    assert_eq!(current(PowerMode::Standby), 285);
+    assert_eq!(current(PowerMode::AccelLowNoise), 235);
+    assert_eq!(current(PowerMode::GyroLowNoise), 385);
+    assert_eq!(current(PowerMode::SixAxisLowNoise), 585);
+
+    // In low-power mode the current grows with the rate of measurements
+    let config = Config {
+        accel_odr: AccelOdr::Hz100,
+        power_mode: PowerMode::AccelLowPower,
+        ..Config::default()
+    };
+    let mut imu = Icm42670::from_interface(FakeDevice::new(), config);
+    assert_eq!(imu.estimated_current_ua(), 11);
+    imu.set_accel_avg(AccelAvg::X16).unwrap();
+    assert_eq!(imu.estimated_current_ua(), 67);
+}
+
+#[test]
+fn enable_fifo_rejects_mismatched_odrs() {
+    let config = Config {
+        accel_odr: AccelOdr::Hz400,
+        ..Config::default()
+    };
+    let mut imu = Icm42670::from_interface(FakeDevice::new(), config);
+    assert_eq!(imu.effective_fifo_rate(), 800.0);
+
+    assert!(matches!(
+        imu.enable_fifo(FifoConfig::default(), &mut NoDelay),
+        Err(Error::SensorError(SensorError::InvalidConfig { .. }))
+    ));
+    // Packets holding only one sensor's data are unaffected
+    imu.enable_fifo(
+        FifoConfig {
+            packet: FifoPacketType::Packet1,
+            ..FifoConfig::default()
+        },
+        &mut NoDelay,
+    )
+    .unwrap();
+}
@@ -0,0 +1,161 @@
+use icm42670::{
+    parse_fifo_records,
+    AccelRange,
+    FifoDataP4,
+    FifoDataSiP1,
+    FifoDataSiP2,
+    FifoDataSiP4,
+    FifoPacketType,
+    FifoRecord,
+    GyroRange,
+    TimestampResolution,
+};
+
+#[test]
+fn fifo_temperature() {
+    // A 16 byte packet with accelerometer and gyroscope data and a temperature
+    // of -10 counts, followed by an 8 byte accelerometer-only packet with a
+    // temperature of 20 counts.
+    let mut raw = [0u8; 24];
+    raw[0] = 0b0110_0000;
+    raw[13] = -10i8 as u8;
+    raw[16] = 0b0100_0000;
+    raw[23] = 20;
+
+    let mut records = [FifoRecord::default(); 2];
+    let count = parse_fifo_records(&raw, &mut records, AccelRange::G2, GyroRange::Deg250);
+
+    assert_eq!(count, 2);
+    assert_eq!(records[0].temp, 20.0);
+    assert_eq!(records[1].temp, 35.0);
+}
+
+#[test]
+fn fifo_temperature_8_byte_packets() {
+    // An accelerometer-only and a gyroscope-only packet, each with X = 100
+    // counts and a temperature of 20 counts
+    let mut accel = [0u8; 8];
+    accel[0] = 0b0100_0000;
+    accel[2] = 100;
+    accel[7] = 20;
+    let mut gyro = accel;
+    gyro[0] = 0b0010_0000;
+
+    let accel = FifoDataSiP1::to_fifodata_si(&accel, 100.0);
+    let gyro = FifoDataSiP2::to_fifodata_si(&gyro, 100.0);
+
+    assert_eq!(accel.t, 35.0);
+    assert_eq!(gyro.t, 35.0);
+    assert!(accel.ax > 9.0 && accel.ax < 10.0);
+    assert!(gyro.gx > 0.017 && gyro.gx < 0.018);
+}
+
+#[test]
+fn fifo_high_resolution() {
+    let mut raw = [0u8; 20];
+    raw[0] = 0b0111_1000;
+    // Accelerometer X = 1, Y = -1, Z = -524_288
+    raw[17] = 0x1_0;
+    raw[3] = 0xFF;
+    raw[4] = 0xFF;
+    raw[18] = 0xF_0;
+    raw[5] = 0x80;
+    // Gyroscope X = 0x12345, Y = -2, Z = 524_287
+    raw[7] = 0x12;
+    raw[8] = 0x34;
+    raw[17] |= 0x5;
+    raw[9] = 0xFF;
+    raw[10] = 0xFF;
+    raw[18] |= 0xE;
+    raw[11] = 0x7F;
+    raw[12] = 0xFF;
+    raw[19] = 0x0_F;
+
+    let data = FifoDataP4::to_fifodata_raw(&raw);
+    assert_eq!((data.ax, data.ay, data.az), (1, -1, -524_288));
+    assert_eq!((data.gx, data.gy, data.gz), (0x12345, -2, 524_287));
+
+    let mut records = [FifoRecord::default(); 1];
+    parse_fifo_records(&raw, &mut records, AccelRange::G2, GyroRange::Deg250);
+    let accel = records[0].accel.unwrap();
+    assert_eq!(accel.x, 1.0 / 32_768.0);
+    assert_eq!(accel.z, -16.0);
+}
+
+#[test]
+fn fifo_high_resolution_scale() {
+    // 16.4 LSB/dps at ±2000 deg/sec, with four additional fractional bits
+    assert_eq!(AccelRange::G16.scale_factor_hires(), 32_768.0);
+    assert_eq!(GyroRange::Deg2000.scale_factor_hires(), 262.4);
+
+    let mut raw = [0u8; 20];
+    raw[0] = 0b0111_1000;
+    // Gyroscope X = 2624, or 10 deg/sec
+    raw[7] = 0x00;
+    raw[8] = 0xA4;
+    raw[17] = 0x0;
+    let data = FifoDataSiP4::to_fifodata_si(&FifoDataP4::to_fifodata_raw(&raw));
+
+    assert!((data.gx - 10f32.to_radians()).abs() < 1e-6);
+}
+
+#[test]
+fn fifo_packet_type_from_header() {
+    assert_eq!(
+        FifoPacketType::from_header(0b0100_0000),
+        Some(FifoPacketType::Packet1)
+    );
+    assert_eq!(
+        FifoPacketType::from_header(0b0010_0000),
+        Some(FifoPacketType::Packet2)
+    );
+    assert_eq!(
+        FifoPacketType::from_header(0b0110_1000),
+        Some(FifoPacketType::Packet3)
+    );
+    assert_eq!(
+        FifoPacketType::from_header(0b0111_1000),
+        Some(FifoPacketType::Packet4)
+    );
+    assert_eq!(FifoPacketType::from_header(0xFF), None);
+}
+
+#[test]
+fn fifo_timestamp() {
+    let mut raw = [0u8; 16];
+    raw[0] = 0b0110_1000;
+    raw[14] = 0xFF;
+    raw[15] = 0xFE;
+
+    let mut records = [FifoRecord::default(); 1];
+    parse_fifo_records(&raw, &mut records, AccelRange::G2, GyroRange::Deg250);
+    assert_eq!(records[0].timestamp, Some(0xFFFE));
+    assert_eq!(
+        records[0].timestamp_us(TimestampResolution::Us1),
+        Some(0xFFFE)
+    );
+    assert_eq!(
+        records[0].timestamp_us(TimestampResolution::Us16),
+        Some(0xFFFE * 16)
+    );
+
+    raw[0] = 0b0110_0000;
+    parse_fifo_records(&raw, &mut records, AccelRange::G2, GyroRange::Deg250);
+    assert_eq!(records[0].timestamp_us(TimestampResolution::Us16), None);
+}
+
+#[test]
+fn fifo_odr_changed() {
+    // Two 16 byte packets, of which the second is the first sampled at a new
+    // accelerometer output data rate.
+    let mut raw = [0u8; 32];
+    raw[0] = 0b0110_0000;
+    raw[16] = 0b0110_0010;
+
+    let mut records = [FifoRecord::default(); 2];
+    parse_fifo_records(&raw, &mut records, AccelRange::G2, GyroRange::Deg250);
+    assert!(!records[0].header.odr_changed());
+    assert!(records[1].header.accel_odr_changed);
+    assert!(!records[1].header.gyro_odr_changed);
+    assert!(records[1].header.odr_changed());
+}
@@ -0,0 +1,350 @@
+use embedded_hal_mock::{
+    delay::MockNoop,
+    i2c::{Mock, Transaction},
+};
+use icm42670::{
+    AccelAvg,
+    AccelBw,
+    AccelLpClock,
+    AccelMode,
+    AccelOdr,
+    AccelRange,
+    Address,
+    ClockSource,
+    Config,
+    FsyncTag,
+    GyroBw,
+    GyroMode,
+    GyroOdr,
+    GyroRange,
+    Icm42670,
+    IntPin,
+    IntStatus,
+    PowerMode,
+    WomConfig,
+};
+
+const ADDR: u8 = Address::Primary as u8;
+
+const PWR_MGMT0: u8 = 0x1F;
+const GYRO_CONFIG0: u8 = 0x20;
+const ACCEL_CONFIG0: u8 = 0x21;
+const GYRO_CONFIG1: u8 = 0x23;
+const ACCEL_CONFIG1: u8 = 0x24;
+const WOM_CONFIG: u8 = 0x27;
+const INTF_CONFIG1: u8 = 0x36;
+const INT_SOURCE0: u8 = 0x2B;
+const INT_SOURCE1: u8 = 0x2C;
+const INT_SOURCE3: u8 = 0x2D;
+const INT_SOURCE4: u8 = 0x2E;
+const MCLK_RDY: u8 = 0x00;
+const BLK_SEL_W: u8 = 0x79;
+const BLK_SEL_R: u8 = 0x7C;
+const MADDR_W: u8 = 0x7A;
+const M_W: u8 = 0x7B;
+const MADDR_R: u8 = 0x7D;
+const M_R: u8 = 0x7E;
+
+/// The transactions of a read-modify-write of `reg`, which currently holds
+/// `current`, replacing the bits in `mask` with `bits`
+fn update(reg: u8, current: u8, bits: u8, mask: u8) -> [Transaction; 2] {
+    [
+        Transaction::write_read(ADDR, vec![reg], vec![current]),
+        Transaction::write(ADDR, vec![reg, (current & !mask) | bits]),
+    ]
+}
+
+/// A driver which expects exactly `transactions`, for a device in `mode`
+fn driver(transactions: &[Transaction], mode: PowerMode) -> Icm42670<icm42670::I2cInterface<Mock>> {
+    let config = Config {
+        power_mode: mode,
+        ..Config::default()
+    };
+
+    Icm42670::from_parts(Mock::new(transactions), Address::Primary, config)
+}
+
+#[test]
+fn bitfield_setters() {
+    // Every other bit of each register is set, and must survive the update
+    let expectations: Vec<Transaction> = [
+        update(ACCEL_CONFIG0, 0xFF, 0b0100_0000, 0b0110_0000),
+        update(GYRO_CONFIG0, 0xFF, 0b0010_0000, 0b0110_0000),
+        update(ACCEL_CONFIG0, 0xFF, 0b0000_1001, 0b0000_1111),
+        update(GYRO_CONFIG0, 0xFF, 0b0000_1000, 0b0000_1111),
+        update(ACCEL_CONFIG1, 0xFF, 0b0000_0011, 0b0000_0111),
+        update(GYRO_CONFIG1, 0xFF, 0b0000_0001, 0b0000_0111),
+        update(INTF_CONFIG1, 0xFF, 0b0000_0000, 0b0000_0011),
+        update(PWR_MGMT0, 0xFF, 0b0000_1111, 0b0001_1111),
+        update(PWR_MGMT0, 0xFF, 0b0000_0010, 0b0000_0011),
+        update(PWR_MGMT0, 0xFF, 0b0000_0100, 0b0000_1100),
+    ]
+    .concat();
+    let mut imu = driver(&expectations, PowerMode::SixAxisLowNoise);
+
+    imu.set_accel_range(AccelRange::G4).unwrap();
+    imu.set_gyro_range(GyroRange::Deg1000).unwrap();
+    imu.set_accel_odr(AccelOdr::Hz100).unwrap();
+    imu.set_gyro_odr(GyroOdr::Hz200).unwrap();
+    imu.set_accel_bw(AccelBw::Hz73).unwrap();
+    imu.set_gyro_bw(GyroBw::Hz180).unwrap();
+    imu.set_clock_source(ClockSource::Rc).unwrap();
+    imu.set_power_mode(PowerMode::SixAxisLowNoise).unwrap();
+    imu.set_accel_mode(AccelMode::LowPower).unwrap();
+    imu.set_gyro_mode(GyroMode::Standby).unwrap();
+
+    imu.free().done();
+}
+
+#[test]
+fn low_power_setters() {
+    let expectations: Vec<Transaction> = [
+        update(ACCEL_CONFIG1, 0x00, 0b0011_0000, 0b0111_0000),
+        update(PWR_MGMT0, 0b0000_0010, 0b1000_0000, 0b1000_0000),
+    ]
+    .concat();
+    let mut imu = driver(&expectations, PowerMode::AccelLowPower);
+
+    imu.set_accel_avg(AccelAvg::X16).unwrap();
+    imu.set_accel_lp_clock(AccelLpClock::Rc).unwrap();
+
+    imu.free().done();
+}
+
+#[test]
+fn bitfield_getters() {
+    // The other bits of each register are set, and must be ignored
+    let read = |reg: u8, value: u8| Transaction::write_read(ADDR, vec![reg], vec![value]);
+    let expectations = [
+        read(ACCEL_CONFIG0, 0b1001_0110),
+        read(GYRO_CONFIG0, 0b1110_1111),
+        read(ACCEL_CONFIG0, 0b1111_1000),
+        read(GYRO_CONFIG0, 0b1111_1010),
+        read(ACCEL_CONFIG1, 0b1110_1101),
+        read(GYRO_CONFIG1, 0b1111_1011),
+        read(ACCEL_CONFIG1, 0b1001_1000),
+        read(INTF_CONFIG1, 0b1111_1101),
+        read(PWR_MGMT0, 0b1110_1100),
+        read(PWR_MGMT0, 0b1111_0010),
+        read(PWR_MGMT0, 0b1110_1101),
+        read(PWR_MGMT0, 0b0111_1111),
+    ];
+    let mut imu = driver(&expectations, PowerMode::SixAxisLowNoise);
+
+    assert_eq!(imu.accel_range().unwrap(), AccelRange::G16);
+    assert_eq!(imu.gyro_range().unwrap(), GyroRange::Deg250);
+    assert_eq!(imu.accel_odr().unwrap(), AccelOdr::Hz200);
+    assert_eq!(imu.gyro_odr().unwrap(), GyroOdr::Hz50);
+    assert_eq!(imu.accel_bandwith().unwrap(), AccelBw::Hz34);
+    assert_eq!(imu.gyro_bandwith().unwrap(), GyroBw::Hz73);
+    assert_eq!(imu.accel_avg().unwrap(), AccelAvg::X4);
+    assert_eq!(imu.clock_source().unwrap(), ClockSource::Pll);
+    assert_eq!(imu.power_mode().unwrap(), PowerMode::GyroLowNoise);
+    assert_eq!(imu.accel_mode().unwrap(), AccelMode::LowPower);
+    assert_eq!(imu.gyro_mode().unwrap(), GyroMode::LowNoise);
+    assert_eq!(
+        imu.accel_lp_clock().unwrap(),
+        AccelLpClock::WakeUpOscillator
+    );
+
+    imu.free().done();
+}
+
+#[test]
+fn int_sources_both_banks() {
+    let read = |reg: u8, value: u8| Transaction::write_read(ADDR, vec![reg], vec![value]);
+    let write = |reg: u8, value: u8| Transaction::write(ADDR, vec![reg, value]);
+    let expectations = [
+        // `INT_SOURCE7` in MREG1, with step and tilt detection routed
+        read(MCLK_RDY, 0b0000_1000),
+        write(BLK_SEL_R, 0x00),
+        write(MADDR_R, 0x30),
+        read(M_R, 0b0010_1000),
+        write(BLK_SEL_R, 0x00),
+        write(BLK_SEL_W, 0x00),
+        // Data ready and significant motion
+        read(INT_SOURCE3, 0b0000_1000),
+        read(INT_SOURCE4, 0b0000_1000),
+    ];
+    let mut imu = driver(&expectations, PowerMode::SixAxisLowNoise);
+
+    let sources = imu.int_sources(IntPin::Int2, &mut MockNoop).unwrap();
+
+    assert_eq!(
+        sources,
+        IntStatus {
+            data_ready: true,
+            significant_motion: true,
+            step: true,
+            tilt: true,
+            ..IntStatus::default()
+        }
+    );
+
+    imu.free().done();
+}
+
+#[test]
+fn set_int_sources_routes_apex() {
+    let read = |reg: u8, value: u8| Transaction::write_read(ADDR, vec![reg], vec![value]);
+    let write = |reg: u8, value: u8| Transaction::write(ADDR, vec![reg, value]);
+    let expectations = [
+        // Data ready
+        write(INT_SOURCE0, 0b0000_1000),
+        write(INT_SOURCE1, 0b0000_0000),
+        // `INT_SOURCE6` in MREG1, whose reserved bits must survive
+        read(MCLK_RDY, 0b0000_1000),
+        write(BLK_SEL_R, 0x00),
+        write(MADDR_R, 0x2F),
+        read(M_R, 0b0000_0101),
+        write(BLK_SEL_R, 0x00),
+        write(BLK_SEL_W, 0x00),
+        read(MCLK_RDY, 0b0000_1000),
+        write(BLK_SEL_W, 0x00),
+        write(MADDR_W, 0x2F),
+        write(M_W, 0b1010_0101),
+        write(BLK_SEL_R, 0x00),
+        write(BLK_SEL_W, 0x00),
+    ];
+    let mut imu = driver(&expectations, PowerMode::SixAxisLowNoise);
+
+    let sources = IntStatus {
+        data_ready: true,
+        step: true,
+        free_fall: true,
+        ..IntStatus::default()
+    };
+    imu.set_int_sources(IntPin::Int1, sources, &mut MockNoop)
+        .unwrap();
+
+    imu.free().done();
+}
+
+#[test]
+fn int_sources_free_fall_and_low_g() {
+    let read = |reg: u8, value: u8| Transaction::write_read(ADDR, vec![reg], vec![value]);
+    let write = |reg: u8, value: u8| Transaction::write(ADDR, vec![reg, value]);
+    let expectations = [
+        // `INT_SOURCE7` in MREG1, with free fall and low-g detection routed
+        read(MCLK_RDY, 0b0000_1000),
+        write(BLK_SEL_R, 0x00),
+        write(MADDR_R, 0x30),
+        read(M_R, 0b1100_0000),
+        write(BLK_SEL_R, 0x00),
+        write(BLK_SEL_W, 0x00),
+        read(INT_SOURCE3, 0x00),
+        read(INT_SOURCE4, 0x00),
+    ];
+    let mut imu = driver(&expectations, PowerMode::SixAxisLowNoise);
+
+    let sources = imu.int_sources(IntPin::Int2, &mut MockNoop).unwrap();
+
+    assert_eq!(
+        sources,
+        IntStatus {
+            free_fall: true,
+            low_g: true,
+            ..IntStatus::default()
+        }
+    );
+
+    imu.free().done();
+}
+
+#[test]
+fn configure_wom_writes_mreg1_before_low_power() {
+    let read = |reg: u8, value: u8| Transaction::write_read(ADDR, vec![reg], vec![value]);
+    let write = |reg: u8, value: u8| Transaction::write(ADDR, vec![reg, value]);
+    let write_mreg = |reg: u8, value: u8| {
+        [
+            read(MCLK_RDY, 0b0000_1000),
+            write(BLK_SEL_W, 0x00),
+            write(MADDR_W, reg),
+            write(M_W, value),
+            write(BLK_SEL_R, 0x00),
+            write(BLK_SEL_W, 0x00),
+        ]
+    };
+    let expectations: Vec<Transaction> = [
+        // `ACCEL_WOM_X_THR` through `ACCEL_WOM_Z_THR`, while the internal
+        // clock is still running
+        write_mreg(0x4B, 64).to_vec(),
+        write_mreg(0x4C, 64).to_vec(),
+        write_mreg(0x4D, 64).to_vec(),
+        vec![write(WOM_CONFIG, 0b0000_0011)],
+        // Routing, preserving data ready
+        vec![
+            read(INT_SOURCE0, 0b0000_1000),
+            read(INT_SOURCE1, 0b0000_0000),
+            write(INT_SOURCE0, 0b0000_1000),
+            write(INT_SOURCE1, 0b0000_0111),
+        ],
+        // Only then is the accelerometer duty-cycled
+        update(PWR_MGMT0, 0b0001_0000, 0b0001_0010, 0b0001_1111).to_vec(),
+    ]
+    .concat();
+    let mut imu = driver(&expectations, PowerMode::Idle);
+
+    imu.configure_wom(WomConfig::default(), IntPin::Int1, &mut MockNoop)
+        .unwrap();
+
+    imu.free().done();
+}
+
+#[test]
+fn fifo_overflow_survives_poll_events() {
+    let read = |reg: u8, value: &[u8]| Transaction::write_read(ADDR, vec![reg], value.to_vec());
+    let expectations = [
+        // `INT_STATUS_DRDY` through `INT_STATUS3`, with `FIFO_FULL_INT` set
+        read(0x39, &[0x00, 0b0000_0010, 0x00, 0x00]),
+        // `INT_STATUS`, which the poll has cleared
+        read(0x3A, &[0x00]),
+    ];
+    let mut imu = driver(&expectations, PowerMode::SixAxisLowNoise);
+
+    assert!(imu.poll_events().unwrap().fifo_full);
+    assert!(imu.fifo_overflowed().unwrap());
+
+    imu.free().done();
+}
+
+#[test]
+fn fsync_selects_pin9_function() {
+    let read = |reg: u8, value: u8| Transaction::write_read(ADDR, vec![reg], vec![value]);
+    let write = |reg: u8, value: u8| Transaction::write(ADDR, vec![reg, value]);
+    let update_mreg = |reg: u8, current: u8, value: u8| {
+        [
+            read(MCLK_RDY, 0b0000_1000),
+            write(BLK_SEL_R, 0x00),
+            write(MADDR_R, reg),
+            read(M_R, current),
+            write(BLK_SEL_R, 0x00),
+            write(BLK_SEL_W, 0x00),
+            read(MCLK_RDY, 0b0000_1000),
+            write(BLK_SEL_W, 0x00),
+            write(MADDR_W, reg),
+            write(M_W, value),
+            write(BLK_SEL_R, 0x00),
+            write(BLK_SEL_W, 0x00),
+        ]
+    };
+    let expectations: Vec<Transaction> = [
+        // `INTF_CONFIG5`, switching pin 9 from INT2 to FSYNC, preserving the
+        // other bits
+        update_mreg(0x21, 0b1000_0001, 0b1000_0011),
+        // `FSYNC_CONFIG` and `TMST_CONFIG1`
+        update_mreg(0x03, 0x00, 0b0111_0000),
+        update_mreg(0x00, 0x00, 0b0000_0010),
+        // ...and back again
+        update_mreg(0x03, 0b0111_0000, 0x00),
+        update_mreg(0x00, 0b0000_0010, 0x00),
+        update_mreg(0x21, 0b1000_0011, 0b1000_0001),
+    ]
+    .concat();
+    let mut imu = driver(&expectations, PowerMode::Idle);
+
+    imu.enable_fsync(FsyncTag::AccelZ, &mut MockNoop).unwrap();
+    imu.disable_fsync(&mut MockNoop).unwrap();
+
+    imu.free().done();
+}
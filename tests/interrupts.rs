@@ -0,0 +1,26 @@
+use icm42670::{EventLog, IntEvent, IntStatus};
+
+#[test]
+fn event_log_keeps_newest_apex_events() {
+    let mut log = EventLog::<2>::new();
+
+    let status = IntStatus {
+        data_ready: true,
+        step: true,
+        tilt: true,
+        ..IntStatus::default()
+    };
+    log.record(&status, 10);
+    assert_eq!(log.len(), 2);
+
+    let status = IntStatus {
+        free_fall: true,
+        ..IntStatus::default()
+    };
+    log.record(&status, 20);
+
+    assert_eq!(log.pop(), Some((IntEvent::Tilt, 10)));
+    assert_eq!(log.pop(), Some((IntEvent::FreeFall, 20)));
+    assert_eq!(log.pop(), None);
+    assert!(log.is_empty());
+}
@@ -0,0 +1,32 @@
+use icm42670::{accelerometer::vector::F32x3, FifoRecord, Inclination, Sample6Dof};
+
+#[test]
+fn inclination_from_accel() {
+    let close = |a: f32, b: f32| (a - b) < 0.1 && (b - a) < 0.1;
+
+    let flat = Inclination::from_accel(F32x3::new(0.0, 0.0, 1.0));
+    assert!(close(flat.pitch_deg, 0.0) && close(flat.roll_deg, 0.0));
+
+    let pitched = Inclination::from_accel(F32x3::new(0.5, 0.0, 0.866));
+    assert!(close(pitched.pitch_deg, 30.0) && close(pitched.roll_deg, 0.0));
+
+    let rolled = Inclination::from_accel(F32x3::new(0.0, -1.0, 0.0));
+    assert!(close(rolled.pitch_deg, 0.0) && close(rolled.roll_deg, -90.0));
+}
+
+#[test]
+fn sample_6dof_from_fifo_record() {
+    let record = FifoRecord {
+        accel: Some(F32x3::new(0.0, 0.0, 1.0)),
+        gyro: None,
+        temp: 25.0,
+        timestamp: Some(1234),
+        ..FifoRecord::default()
+    };
+
+    let sample = Sample6Dof::from(record);
+
+    assert_eq!(sample.accel_g, [0.0, 0.0, 1.0]);
+    assert_eq!(sample.gyro_dps, [0.0; 3]);
+    assert_eq!(sample.timestamp, Some(1234));
+}
@@ -0,0 +1,120 @@
+use std::{cell::RefCell, convert::Infallible, rc::Rc};
+
+use embedded_hal_mock::{
+    pin::{Mock as PinMock, State, Transaction as PinTransaction},
+    spi::{Mock as SpiMock, Transaction as SpiTransaction},
+};
+use icm42670::{AccelRange, Config, Error, HalfDuplexSpi, Icm42670, SensorError};
+
+const DEVICE_CONFIG: u8 = 0x01;
+const ACCEL_CONFIG0: u8 = 0x21;
+const WHO_AM_I: u8 = 0x75;
+
+/// Chip select transitions for `n` transactions
+fn selects(n: usize) -> Vec<PinTransaction> {
+    (0..n)
+        .flat_map(|_| {
+            [
+                PinTransaction::set(State::Low),
+                PinTransaction::set(State::High),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn new_spi_reads_device_id() {
+    // The most significant bit of the address is set for reads
+    let spi = SpiMock::new(&[
+        SpiTransaction::write(vec![WHO_AM_I | 0x80]),
+        SpiTransaction::transfer(vec![0x00], vec![0x42]),
+    ]);
+    let cs = PinMock::new(&selects(1));
+
+    let result = Icm42670::new_spi(spi.clone(), cs.clone());
+
+    assert!(matches!(
+        result,
+        Err(Error::SensorError(SensorError::BadChip { found: 0x42 }))
+    ));
+    spi.clone().done();
+    cs.clone().done();
+}
+
+#[test]
+fn register_read_modify_write() {
+    let spi = SpiMock::new(&[
+        SpiTransaction::write(vec![ACCEL_CONFIG0 | 0x80]),
+        SpiTransaction::transfer(vec![0x00], vec![0b0000_1001]),
+        // ...and cleared for writes
+        SpiTransaction::write(vec![ACCEL_CONFIG0]),
+        SpiTransaction::write(vec![0b0100_1001]),
+    ]);
+    let cs = PinMock::new(&selects(2));
+    let mut imu = Icm42670::from_parts_spi(spi, cs, Config::default());
+
+    imu.set_accel_range(AccelRange::G4).unwrap();
+
+    let (mut spi, mut cs) = imu.free();
+    spi.done();
+    cs.done();
+}
+
+/// A half-duplex bus which logs each phase of every transaction, and answers
+/// every read with `response`
+struct HalfDuplex {
+    log: Rc<RefCell<Vec<Phase>>>,
+    response: u8,
+}
+
+#[derive(Debug, PartialEq)]
+enum Phase {
+    Write(Vec<u8>),
+    Read(usize),
+}
+
+impl HalfDuplexSpi for HalfDuplex {
+    type Error = Infallible;
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Infallible> {
+        self.log.borrow_mut().push(Phase::Write(data.to_vec()));
+
+        Ok(())
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<(), Infallible> {
+        self.log.borrow_mut().push(Phase::Read(buffer.len()));
+        buffer.fill(self.response);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn new_spi_3wire_selects_3wire_mode_first() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let spi = HalfDuplex {
+        log: log.clone(),
+        response: 0x42,
+    };
+    let cs = PinMock::new(&selects(2));
+
+    let result = Icm42670::new_spi_3wire(spi, cs.clone());
+
+    assert!(matches!(
+        result,
+        Err(Error::SensorError(SensorError::BadChip { found: 0x42 }))
+    ));
+    // `DEVICE_CONFIG` is written, clearing `SPI_AP_4WIRE`, before the data
+    // line is turned around to read the device ID
+    assert_eq!(
+        *log.borrow(),
+        [
+            Phase::Write(vec![DEVICE_CONFIG]),
+            Phase::Write(vec![0x00]),
+            Phase::Write(vec![WHO_AM_I | 0x80]),
+            Phase::Read(1),
+        ]
+    );
+    cs.clone().done();
+}